@@ -0,0 +1,30 @@
+//! Fuzz `aes_dec_cbc` with arbitrary keys, IVs, ciphertexts and padding modes.
+//!
+//! The first byte selects the key length and the padding mode, followed by the
+//! key, a 16-byte IV and the ciphertext. Decryption of arbitrary input must
+//! never panic; invalid input has to result in an error.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use soft_aes::aes::{aes_dec_cbc, AES_BLOCK_SIZE};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+
+    // Also exercise invalid key lengths
+    let key_len = [16, 24, 32, 0, 15, 33][(selector % 6) as usize];
+    let padding = [None, Some("PKCS7"), Some("0x80")][(selector / 6 % 3) as usize];
+
+    if rest.len() < key_len + AES_BLOCK_SIZE {
+        return;
+    }
+
+    let (key, rest) = rest.split_at(key_len);
+    let (iv, ciphertext) = rest.split_at(AES_BLOCK_SIZE);
+    let iv: [u8; AES_BLOCK_SIZE] = iv.try_into().unwrap();
+
+    let _ = aes_dec_cbc(ciphertext, key, &iv, padding);
+});
@@ -0,0 +1,22 @@
+//! Fuzz `aes_dec_ecb` with arbitrary keys, ciphertexts and padding modes.
+//!
+//! The first byte selects the key length and the padding mode. Decryption of
+//! arbitrary input must never panic; invalid input has to result in an error.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use soft_aes::aes::aes_dec_ecb;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+
+    // Also exercise invalid key lengths
+    let key_len = [16, 24, 32, 0, 15, 33][(selector % 6) as usize].min(rest.len());
+    let padding = [None, Some("PKCS7"), Some("0x80")][(selector / 6 % 3) as usize];
+
+    let (key, ciphertext) = rest.split_at(key_len);
+    let _ = aes_dec_ecb(ciphertext, key, padding);
+});
@@ -0,0 +1,21 @@
+//! Fuzz `pkcs7_unpad` with arbitrary data.
+//!
+//! Unpadding must never panic. On success the data may only shrink by the
+//! value of its last byte and all removed bytes must equal that value.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use soft_aes::padding::pkcs7_unpad;
+
+fuzz_target!(|data: &[u8]| {
+    let mut unpadded = data.to_vec();
+
+    if pkcs7_unpad(&mut unpadded).is_ok() {
+        let padding_len = *data.last().unwrap() as usize;
+        assert_eq!(unpadded.len() + padding_len, data.len());
+        assert!(data[unpadded.len()..]
+            .iter()
+            .all(|&x| x as usize == padding_len));
+    }
+});
@@ -0,0 +1,19 @@
+//! Fuzz `unpad_80` with arbitrary data.
+//!
+//! Unpadding must never panic. On success the removed bytes must be a single
+//! 0x80 byte followed only by 0x00 bytes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use soft_aes::padding::unpad_80;
+
+fuzz_target!(|data: &[u8]| {
+    let mut unpadded = data.to_vec();
+
+    if unpad_80(&mut unpadded).is_ok() {
+        let removed = &data[unpadded.len()..];
+        assert_eq!(removed[0], 0x80);
+        assert!(removed[1..].iter().all(|&x| x == 0x00));
+    }
+});
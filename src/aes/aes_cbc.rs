@@ -14,10 +14,27 @@
 //! - `aes_enc_cbc`: Encrypts data using AES in CBC mode. It supports optional
 //!   PKCS#7 and 0x80 padding for data that is not a multiple of the AES block size.
 //!
+//! - `aes_enc_cbc_reuse`: Same as `aes_enc_cbc`, but writes the ciphertext
+//!   into a caller-provided buffer that can be reused across calls.
+//!
+//! - `aes_enc_cbc_chained`: Same as `aes_enc_cbc`, but also returns the last
+//!   ciphertext block to continue the CBC chain in a subsequent call.
+//!
 //! - `aes_dec_cbc`: Decrypts data that was encrypted using AES in CBC mode.
 //!   It also supports the removal of PKCS#7 or 0x80 padding if it was applied during
 //!   encryption.
 //!
+//! - `aes_dec_cbc_in_place_unpad`: Same as `aes_dec_cbc`, but decrypts the
+//!   buffer in place and truncates it to remove the padding.
+//!
+//! - `aes_enc_cbc_framed` and `aes_dec_cbc_framed`: Encrypt into and decrypt
+//!   from the framing `[iv_len: u8][iv][ciphertext]`, which carries the IV
+//!   together with the ciphertext.
+//!
+//! - `aes_enc_cbc_explicit_pad` and `aes_dec_cbc_explicit_pad`: Encrypt with
+//!   PKCS#7 padding and return the padding length, and decrypt by truncating
+//!   a padding length given out-of-band.
+//!
 //! The implementation requires both an encryption key and an initialization
 //! vector (IV) of valid lengths for AES (128, 192, or 256 bits for the key,
 //! and 128 bits for the IV). This module closely integrates with the core AES
@@ -65,42 +82,259 @@ use std::error::Error;
 ///
 /// # Parameters
 /// - `plaintext`: The data to encrypt. It should be a multiple of
-///                `AES_BLOCK_SIZE` unless padding is applied.
+///   `AES_BLOCK_SIZE` unless padding is applied.
 /// - `key`: The encryption key.
 /// - `iv`: The initialization vector (IV) for CBC mode.
 /// - `padding`: Optional padding method. Supported values are `None` (default),
-///              `PKCS7`, and `0x80`.
+///   `PKCS7`, and `0x80`.
 ///
 /// # Returns
 /// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the encrypted data
 /// or an error.
 pub fn aes_enc_cbc(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let plaintext = plaintext.as_ref();
+    let key = key.as_ref();
+
+    // The output is allocated once with its final size and encrypted in place
+    let mut ciphertext = Vec::new();
+    enc_cbc_in_place(plaintext, key, iv, padding, &mut ciphertext)?;
+
+    Ok(ciphertext)
+}
+
+/// Encrypt data using AES in CBC mode and return the IV for the next record.
+///
+/// When a sequence of records continues one CBC chain, the last ciphertext
+/// block of a record is the IV of the next one. This function returns that
+/// block together with the ciphertext, so the caller does not have to slice it
+/// from the tail. For unpadded records, encrypting the records one after
+/// another with the returned IV gives the same ciphertext as encrypting their
+/// concatenation in a single call.
+///
+/// # Parameters
+/// - `plaintext`: The data to encrypt. It should be a multiple of
+///   `AES_BLOCK_SIZE` unless padding is applied.
+/// - `key`: The encryption key.
+/// - `iv`: The initialization vector (IV) for CBC mode.
+/// - `padding`: Optional padding method. Supported values are `None` (default),
+///   `PKCS7`, and `0x80`.
+///
+/// # Returns
+/// Returns a `Result<(Vec<u8>, [u8; AES_BLOCK_SIZE]), Box<dyn Error>>`
+/// containing the encrypted data and the last ciphertext block, or an error.
+/// If the ciphertext is empty, the returned block is `iv`.
+pub fn aes_enc_cbc_chained(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+) -> Result<(Vec<u8>, [u8; AES_BLOCK_SIZE]), Box<dyn Error>> {
+    let ciphertext = aes_enc_cbc(plaintext, key, iv, padding)?;
+
+    let mut next_iv = *iv;
+    if let Some(last_block) = ciphertext.rchunks_exact(AES_BLOCK_SIZE).next() {
+        next_iv.copy_from_slice(last_block);
+    }
+
+    Ok((ciphertext, next_iv))
+}
+
+/// Encrypt data using AES in CBC mode into the framing
+/// `[iv_len: u8][iv][ciphertext]`.
+///
+/// The ciphertext is prefixed with a length byte and the IV, so that the IV is
+/// transmitted together with the data. The length byte is always
+/// `AES_BLOCK_SIZE`. The output can be decrypted with `aes_dec_cbc_framed`.
+///
+/// # Parameters
+/// - `plaintext`: The data to encrypt. It should be a multiple of
+///   `AES_BLOCK_SIZE` unless padding is applied.
+/// - `key`: The encryption key.
+/// - `iv`: The initialization vector (IV) for CBC mode, written to the frame.
+/// - `padding`: Optional padding method. Supported values are `None` (default),
+///   `PKCS7`, and `0x80`.
+///
+/// # Returns
+/// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the framed data or
+/// an error.
+pub fn aes_enc_cbc_framed(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let ciphertext = aes_enc_cbc(plaintext, key, iv, padding)?;
+
+    let mut framed = Vec::with_capacity(1 + AES_BLOCK_SIZE + ciphertext.len());
+    framed.push(AES_BLOCK_SIZE as u8);
+    framed.extend_from_slice(iv);
+    framed.extend_from_slice(&ciphertext);
+
+    Ok(framed)
+}
+
+/// Encrypt data using AES in CBC mode with PKCS#7 padding and return the
+/// padding length.
+///
+/// For formats that store the padding length out-of-band, the number of
+/// padding bytes added is returned together with the ciphertext. It is always
+/// in `1..=AES_BLOCK_SIZE`, as PKCS#7 adds a full block to aligned data. The
+/// ciphertext can be decrypted with `aes_dec_cbc_explicit_pad`.
+///
+/// # Parameters
+/// - `plaintext`: The data to encrypt.
+/// - `key`: The encryption key.
+/// - `iv`: The initialization vector (IV) for CBC mode.
+///
+/// # Returns
+/// Returns a `Result<(Vec<u8>, u8), Box<dyn Error>>` containing the encrypted
+/// data and the number of padding bytes, or an error.
+pub fn aes_enc_cbc_explicit_pad(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+) -> Result<(Vec<u8>, u8), Box<dyn Error>> {
+    let plaintext = plaintext.as_ref();
+
+    let ciphertext = aes_enc_cbc(plaintext, key, iv, Some("PKCS7"))?;
+    let pad_len = (ciphertext.len() - plaintext.len()) as u8;
+
+    Ok((ciphertext, pad_len))
+}
+
+/// Encrypt data using AES in CBC mode, writing the ciphertext into a
+/// caller-provided buffer.
+///
+/// This variant of `aes_enc_cbc` clears `scratch` and reuses it as the output
+/// buffer, so that encrypting many messages in a loop does not allocate a new
+/// `Vec` for every call once `scratch` has grown large enough. The plaintext
+/// is copied into `scratch`, padded and encrypted in place.
+///
+/// # Parameters
+/// - `plaintext`: The data to encrypt. It should be a multiple of
+///   `AES_BLOCK_SIZE` unless padding is applied.
+/// - `key`: The encryption key.
+/// - `iv`: The initialization vector (IV) for CBC mode.
+/// - `padding`: Optional padding method. Supported values are `None` (default),
+///   `PKCS7`, and `0x80`.
+/// - `scratch`: The output buffer. Its previous contents are discarded.
+///
+/// # Returns
+/// Returns `Ok(())` with `scratch` holding exactly the ciphertext, or an
+/// error. On error `scratch` is left empty.
+pub fn aes_enc_cbc_reuse(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+    scratch: &mut Vec<u8>,
+) -> Result<(), Box<dyn Error>> {
+    let plaintext = plaintext.as_ref();
+    let key = key.as_ref();
+
+    scratch.clear();
+
+    let result = enc_cbc_in_place(plaintext, key, iv, padding, scratch);
+    if result.is_err() {
+        scratch.clear();
+    }
+
+    result
+}
+
+/// Copy, pad and CBC-encrypt `plaintext` into `buffer`, which must be empty.
+fn enc_cbc_in_place(
     plaintext: &[u8],
     key: &[u8],
     iv: &[u8; AES_BLOCK_SIZE],
     padding: Option<&str>,
-) -> Result<Vec<u8>, Box<dyn Error>> {
+    buffer: &mut Vec<u8>,
+) -> Result<(), Box<dyn Error>> {
     let block_size = AES_BLOCK_SIZE;
-    let mut data = plaintext.to_vec();
 
-    // Apply padding if necessary
-    match padding {
-        Some("PKCS7") => pkcs7_pad(&mut data, block_size)?,
-        Some("0x80") => pad_80(&mut data, block_size)?,
-        None if data.len() % block_size != 0 => {
+    // Reject unknown padding methods, which would otherwise leave the data
+    // unpadded
+    if let Some(other) = padding {
+        if other != "PKCS7" && other != "0x80" {
             return Err(Box::new(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
-                "AES ENC CBC Error: Plaintext must be a multiple of AES_BLOCK_SIZE for 'None' padding",
+                format!(
+                    "AES ENC CBC Error: Unsupported padding '{}', expected 'PKCS7' or '0x80'",
+                    other
+                ),
             )));
         }
+    }
+
+    let padded_len = cbc_padded_len(plaintext.len(), padding).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES ENC CBC Error: Input too large, got {} bytes",
+                plaintext.len()
+            ),
+        )
+    })?;
+    buffer.try_reserve_exact(padded_len)?;
+    buffer.extend_from_slice(plaintext);
+
+    // Apply padding if necessary
+    match padding {
+        Some("PKCS7") => pkcs7_pad(buffer, block_size)?,
+        Some("0x80") => pad_80(buffer, block_size)?,
         _ => {}
     }
 
-    let mut ciphertext = Vec::with_capacity(data.len());
-    let mut previous_block = iv.clone();
+    // Every byte must be covered by a full block, or it would be left
+    // unencrypted
+    if !buffer.len().is_multiple_of(block_size) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "AES ENC CBC Error: Plaintext must be a multiple of AES_BLOCK_SIZE for 'None' padding",
+        )));
+    }
+
+    let (expanded_key, nr) = key_schedule(key)?;
+    cbc_encrypt_generic(buffer, iv, |block| {
+        encrypt_block_expanded(block, &expanded_key, nr)
+    })
+}
+
+/// CBC-encrypt block aligned `data` in place with an arbitrary block function.
+///
+/// This is the chaining logic of `aes_enc_cbc`, which passes AES encryption
+/// as `encrypt_block`. Tests can pass a trivial block function to check the
+/// chaining in isolation. Padding must already be applied.
+///
+/// Returns an error without modifying `data` if its length is not a multiple
+/// of `AES_BLOCK_SIZE`.
+pub(crate) fn cbc_encrypt_generic<F>(
+    data: &mut [u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    encrypt_block: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn(&[u8; AES_BLOCK_SIZE]) -> [u8; AES_BLOCK_SIZE],
+{
+    if !data.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES ENC CBC Error: Data must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                data.len()
+            ),
+        )));
+    }
+
+    let mut previous_block = *iv;
 
-    // Encrypt each block
-    for block in data.chunks(block_size) {
+    // Encrypt each block in place
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
         let mut block_array = [0u8; AES_BLOCK_SIZE];
         block_array.copy_from_slice(block);
 
@@ -109,42 +343,70 @@ pub fn aes_enc_cbc(
             *b ^= *p;
         }
 
-        let encrypted_block = aes_enc_block(&block_array, key)?;
-        previous_block = encrypted_block;
-        ciphertext.extend_from_slice(&previous_block);
+        previous_block = encrypt_block(&block_array);
+        block.copy_from_slice(&previous_block);
     }
 
-    Ok(ciphertext)
+    Ok(())
+}
+
+/// Length of the CBC ciphertext for a plaintext of `len` bytes, or `None` if
+/// the padded length would be too large to allocate.
+fn cbc_padded_len(len: usize, padding: Option<&str>) -> Option<usize> {
+    match padding {
+        Some("PKCS7") | Some("0x80") => padded_len(len, AES_BLOCK_SIZE),
+        _ => Some(len),
+    }
 }
 
 /// Decrypt data using AES in CBC mode with optional padding removal.
 ///
 /// # Parameters
 /// - `ciphertext`: The encrypted data to decrypt. It should be a multiple of
-///                 `AES_BLOCK_SIZE`.
+///   `AES_BLOCK_SIZE`.
 /// - `key`: The decryption key.
 /// - `iv`: The initialization vector (IV) used during encryption for CBC mode.
 /// - `padding`: Optional padding method used during encryption. Supported values
-///              are `None` (default), `PKCS7`, and `0x80`.
+///   are `None` (default), `PKCS7`, and `0x80`.
 ///
 /// # Returns
 /// Returns a `Result<Vec<u8>, Box<dyn std::error::Error>>` containing the
 /// decrypted data or an error.
 pub fn aes_dec_cbc(
-    ciphertext: &[u8],
-    key: &[u8],
+    ciphertext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
     iv: &[u8; AES_BLOCK_SIZE],
     padding: Option<&str>,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    if ciphertext.len() % AES_BLOCK_SIZE != 0 {
+    let ciphertext = ciphertext.as_ref();
+    let key = key.as_ref();
+
+    // Reject unknown padding methods, which would otherwise leave the padding
+    // in the plaintext
+    if let Some(other) = padding {
+        if other != "PKCS7" && other != "0x80" {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES DEC CBC Error: Unsupported padding '{}', expected 'PKCS7' or '0x80'",
+                    other
+                ),
+            )));
+        }
+    }
+
+    if !ciphertext.len().is_multiple_of(AES_BLOCK_SIZE) {
         return Err(Box::new(std::io::Error::new(
             std::io::ErrorKind::InvalidInput,
-            "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE",
+            format!(
+                "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                ciphertext.len()
+            ),
         )));
     }
 
     let mut plaintext = Vec::with_capacity(ciphertext.len());
-    let mut previous_block = iv.clone();
+    let mut previous_block = *iv;
 
     // Decrypt each block
     for block in ciphertext.chunks(AES_BLOCK_SIZE) {
@@ -170,3 +432,234 @@ pub fn aes_dec_cbc(
 
     Ok(plaintext)
 }
+
+/// Decrypt data using AES in CBC mode in place, removing padding by truncation.
+///
+/// This variant of `aes_dec_cbc` overwrites the ciphertext in `buf` with the
+/// plaintext and then truncates `buf` to remove the padding, so no separate
+/// output buffer is allocated. Each ciphertext block is saved before it is
+/// overwritten, as it is needed to chain the next block.
+///
+/// # Parameters
+/// - `buf`: The encrypted data, replaced by the decrypted data. It should be a
+///   multiple of `AES_BLOCK_SIZE`.
+/// - `key`: The decryption key.
+/// - `iv`: The initialization vector (IV) used during encryption for CBC mode.
+/// - `padding`: Optional padding method used during encryption. Supported values
+///   are `None` (default), `PKCS7`, and `0x80`.
+///
+/// # Returns
+/// Returns a `Result<usize, Box<dyn Error>>` containing the length of the
+/// plaintext left in `buf`, or an error. If the ciphertext length is invalid,
+/// `buf` is left unchanged. If decryption or padding removal fails, `buf` is
+/// cleared.
+pub fn aes_dec_cbc_in_place_unpad(
+    buf: &mut Vec<u8>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+) -> Result<usize, Box<dyn Error>> {
+    let key = key.as_ref();
+
+    if !buf.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                buf.len()
+            ),
+        )));
+    }
+
+    let result = dec_cbc_in_place(buf, key, iv, padding);
+    if result.is_err() {
+        buf.clear();
+    }
+
+    result.map(|_| buf.len())
+}
+
+/// Decrypt data in the framing `[iv_len: u8][iv][ciphertext]` using AES in CBC
+/// mode.
+///
+/// The length byte is read first and must be `AES_BLOCK_SIZE`. The IV is then
+/// taken from the following bytes and the remainder is decrypted as with
+/// `aes_dec_cbc`. All lengths are checked before slicing, so malformed input
+/// results in an error rather than a panic.
+///
+/// # Parameters
+/// - `data`: The framed data, as produced by `aes_enc_cbc_framed`.
+/// - `key`: The decryption key.
+/// - `padding`: Optional padding method used during encryption. Supported values
+///   are `None` (default), `PKCS7`, and `0x80`.
+///
+/// # Returns
+/// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the decrypted data,
+/// or an error if the frame is empty, the length byte is not `AES_BLOCK_SIZE`,
+/// the frame is shorter than the IV, or decryption fails.
+pub fn aes_dec_cbc_framed(
+    data: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    padding: Option<&str>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = data.as_ref();
+
+    let (&iv_len, rest) = data.split_first().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "AES DEC CBC Error: Framed data is empty",
+        )
+    })?;
+
+    let iv_len = iv_len as usize;
+    if iv_len != AES_BLOCK_SIZE {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES DEC CBC Error: IV length must be {} bytes, got {}",
+                AES_BLOCK_SIZE, iv_len
+            ),
+        )));
+    }
+
+    if rest.len() < iv_len {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES DEC CBC Error: Framed data too short for a {}-byte IV, got {} bytes",
+                iv_len,
+                rest.len()
+            ),
+        )));
+    }
+
+    let (iv_bytes, ciphertext) = rest.split_at(iv_len);
+    let mut iv = [0u8; AES_BLOCK_SIZE];
+    iv.copy_from_slice(iv_bytes);
+
+    aes_dec_cbc(ciphertext, key, &iv, padding)
+}
+
+/// Decrypt data using AES in CBC mode and remove a padding of known length.
+///
+/// The counterpart of `aes_enc_cbc_explicit_pad`: the ciphertext is decrypted
+/// without padding removal and then truncated by `pad_len` bytes. The padding
+/// bytes themselves are not inspected, as the length is taken from `pad_len`
+/// instead of the plaintext.
+///
+/// # Parameters
+/// - `ciphertext`: The encrypted data to decrypt. It should be a non-empty
+///   multiple of `AES_BLOCK_SIZE`.
+/// - `key`: The decryption key.
+/// - `iv`: The initialization vector (IV) used during encryption for CBC mode.
+/// - `pad_len`: The number of padding bytes, in `1..=AES_BLOCK_SIZE`.
+///
+/// # Returns
+/// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the decrypted data,
+/// or an error if `pad_len` is out of range, exceeds the ciphertext length, or
+/// decryption fails.
+pub fn aes_dec_cbc_explicit_pad(
+    ciphertext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+    pad_len: u8,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let ciphertext = ciphertext.as_ref();
+    let pad_len = pad_len as usize;
+
+    if pad_len == 0 || pad_len > AES_BLOCK_SIZE || pad_len > ciphertext.len() {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES DEC CBC Error: Padding length must be 1 to {} bytes and fit the ciphertext, got {}",
+                AES_BLOCK_SIZE, pad_len
+            ),
+        )));
+    }
+
+    let mut plaintext = aes_dec_cbc(ciphertext, key, iv, None)?;
+    plaintext.truncate(plaintext.len() - pad_len);
+
+    Ok(plaintext)
+}
+
+/// CBC-decrypt `buffer` in place and remove the padding.
+fn dec_cbc_in_place(
+    buffer: &mut Vec<u8>,
+    key: &[u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    padding: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    // Reject unknown padding methods, which would otherwise leave the padding
+    // in the plaintext
+    if let Some(other) = padding {
+        if other != "PKCS7" && other != "0x80" {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES DEC CBC Error: Unsupported padding '{}', expected 'PKCS7' or '0x80'",
+                    other
+                ),
+            )));
+        }
+    }
+
+    let (expanded_key, nr) = key_schedule(key)?;
+    cbc_decrypt_generic(buffer, iv, |block| {
+        decrypt_block_expanded(block, &expanded_key, nr)
+    })?;
+
+    // Remove padding if it was used during encryption
+    match padding {
+        Some("PKCS7") => pkcs7_unpad(buffer)?,
+        Some("0x80") => unpad_80(buffer)?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// CBC-decrypt block aligned `data` in place with an arbitrary block function.
+///
+/// This is the counterpart of `cbc_encrypt_generic`. Padding is not removed.
+///
+/// Returns an error without modifying `data` if its length is not a multiple
+/// of `AES_BLOCK_SIZE`.
+pub(crate) fn cbc_decrypt_generic<F>(
+    data: &mut [u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    decrypt_block: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn(&[u8; AES_BLOCK_SIZE]) -> [u8; AES_BLOCK_SIZE],
+{
+    if !data.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                data.len()
+            ),
+        )));
+    }
+
+    let mut previous_block = *iv;
+
+    // Decrypt each block in place
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        // Keep the ciphertext block for chaining before it is overwritten
+        let mut ciphertext_block = [0u8; AES_BLOCK_SIZE];
+        ciphertext_block.copy_from_slice(block);
+
+        let mut decrypted_block = decrypt_block(&ciphertext_block);
+        // XOR decrypted block with previous ciphertext block (or IV for first block)
+        for (b, p) in decrypted_block.iter_mut().zip(previous_block.iter()) {
+            *b ^= *p;
+        }
+
+        block.copy_from_slice(&decrypted_block);
+        previous_block = ciphertext_block;
+    }
+
+    Ok(())
+}
@@ -22,6 +22,36 @@
 //!
 //! - `generate_subkey`: Generates subkeys used in the CMAC algorithm from a given AES key.
 //!
+//! - `SubKeys`: Bundles the subkeys `K1` and `K2` with a `Debug` implementation that redacts them.
+//!
+//! - `Cmac`: Holds the expanded key and the subkeys of one key, to compute the AES-CMAC of many
+//!   messages without repeating the key setup.
+//!
+//! - `Mac`: A trait for keyed 128-bit MACs with constant-time verification, implemented by `Cmac`,
+//!   for code that is generic over the MAC algorithm.
+//!
+//! - `aes_cmac_verify`: Verifies a full or truncated AES-CMAC tag in constant time.
+//!
+//! - `firmware_mac` and `verify_firmware_mac`: Compute and verify the AES-CMAC of a firmware
+//!   image with a fixed length framing.
+//!
+//! - `aes_cmac_fields`: Computes the AES-CMAC over a sequence of fields, each prefixed with its
+//!   length, so that the field boundaries are authenticated.
+//!
+//! - `aes_cmac_256`: Computes a non-standard 256-bit tag from two domain-separated AES-CMACs.
+//!
+//! - `aes_cmac_mic32` and `verify_mic32`: Compute and verify a 32-bit message integrity code
+//!   (MIC) truncated from the AES-CMAC, as used by some constrained protocols.
+//!
+//! - `key_check_tag` and `verify_key_check`: Compute and verify a tag that identifies a key,
+//!   to reject a wrong key before decrypting.
+//!
+//! - `aes_cmac_with_zero_run`: Computes the AES-CMAC of `prefix || zeros || suffix` without
+//!   allocating the run of zero bytes, e.g. for sparse images.
+//!
+//! - `aes_cmac_mmap`: Computes the AES-CMAC of a file by memory-mapping it (requires the `mmap`
+//!   feature).
+//!
 //! # Usage
 //!
 //! AES-CMAC is suitable for various cryptographic applications, especially in systems where AES is
@@ -70,7 +100,6 @@
 //! - This implementation aims for clarity and adherence to the standard. For high-performance
 //!   requirements, further optimizations may be necessary.
 use super::aes_core::*;
-use crate::padding::pad_80;
 
 use std::error::Error;
 
@@ -79,18 +108,28 @@ const CONST_RB: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x87];
 
 /// Generate subkeys for AES-CMAC.
 ///
+/// `L = AES(K, const_Zero)` is computed with the AES variant of the key, so
+/// the subkeys of AES-192 and AES-256 keys depend on the full key schedule.
+/// NIST SP 800-38B, Appendix D lists `L`, `K1` and `K2` for all three key
+/// sizes.
+///
 /// # Parameters
 /// - `key`: AES-128, AES-192 or AES-256 key.
 ///
 /// # Returns
 /// Returns a tuple of two 128-bit subkeys `(K1, K2)` or an error if
 /// the encryption fails.
-pub fn generate_subkey(key: &[u8]) -> Result<([u8; 16], [u8; 16]), Box<dyn Error>> {
-    // Step 1: L := AES-128(K, const_Zero)
-    let l = aes_enc_block(&CONST_ZERO, key)?;
+pub fn generate_subkey(key: impl AsRef<[u8]>) -> Result<([u8; 16], [u8; 16]), Box<dyn Error>> {
+    // Step 1: L := AES(K, const_Zero)
+    let l = aes_enc_block(&CONST_ZERO, key.as_ref())?;
 
+    Ok(subkeys_from_l(&l))
+}
+
+/// Derive the subkeys `(K1, K2)` from `L = AES(K, const_Zero)`.
+fn subkeys_from_l(l: &[u8; 16]) -> ([u8; 16], [u8; 16]) {
     // Step 2: Generate K1
-    let mut k1 = left_shift_one_bit(&l);
+    let mut k1 = left_shift_one_bit(l);
     if l[0] & 0x80 != 0 {
         // if MSB(L) == 1
         for (k1_byte, rb_byte) in k1.iter_mut().zip(CONST_RB.iter()) {
@@ -107,7 +146,219 @@ pub fn generate_subkey(key: &[u8]) -> Result<([u8; 16], [u8; 16]), Box<dyn Error
         }
     }
 
-    Ok((k1, k2))
+    (k1, k2)
+}
+
+/// The AES-CMAC subkeys `K1` and `K2` derived from a key.
+///
+/// This bundles the result of `generate_subkey` in a named type for
+/// constructions built on the CMAC subkeys, such as S2V. The `Debug`
+/// implementation redacts the subkey bytes, so that the subkeys cannot be
+/// leaked by accidentally logging the struct.
+#[derive(Clone)]
+pub struct SubKeys {
+    k1: [u8; 16],
+    k2: [u8; 16],
+}
+
+impl SubKeys {
+    /// Derive the subkeys from an AES key.
+    ///
+    /// # Parameters
+    /// - `key`: AES-128, AES-192 or AES-256 key.
+    ///
+    /// # Returns
+    /// Returns the subkeys, or an error if the key length is invalid.
+    pub fn from_key(key: impl AsRef<[u8]>) -> Result<Self, Box<dyn Error>> {
+        let (k1, k2) = generate_subkey(key)?;
+
+        Ok(Self { k1, k2 })
+    }
+
+    /// The subkey `K1`, used when the last message block is complete.
+    pub fn k1(&self) -> &[u8; 16] {
+        &self.k1
+    }
+
+    /// The subkey `K2`, used when the last message block is padded.
+    pub fn k2(&self) -> &[u8; 16] {
+        &self.k2
+    }
+}
+
+impl std::fmt::Debug for SubKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubKeys")
+            .field("k1", &"<redacted>")
+            .field("k2", &"<redacted>")
+            .finish()
+    }
+}
+
+/// AES-CMAC with the key setup computed once.
+///
+/// `aes_cmac` expands the key and derives the subkeys `K1` and `K2` on every
+/// call. `Cmac` does this once in `Cmac::new` and reuses the result for every
+/// message, which pays off when many messages are authenticated under the
+/// same key. The tags are identical to those of `aes_cmac`.
+///
+/// `Cmac` is `Clone`, `Send` and `Sync`, so one instance can be shared between
+/// threads. Its `Debug` implementation redacts the key material.
+///
+/// # Example
+///
+/// ```
+/// use crate::soft_aes::aes::{aes_cmac, Cmac};
+///
+/// let key = b"Very secret key.";
+/// let cmac = Cmac::new(key).unwrap();
+///
+/// for message in [&b"first"[..], b"second", b"third"] {
+///     assert_eq!(cmac.mac(message), aes_cmac(message, key).unwrap());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Cmac {
+    expanded_key: [u8; 240],
+    nr: usize,
+    subkeys: SubKeys,
+}
+
+impl Cmac {
+    /// Expand the key and derive the subkeys.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - AES-128, AES-192 or AES-256 key.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new instance, or an error if the key length
+    /// is invalid.
+    pub fn new(key: impl AsRef<[u8]>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_key_schedule(key_schedule(key.as_ref())?))
+    }
+
+    /// Create an instance for a key fixed by an algorithm, without the weak
+    /// key check.
+    ///
+    /// See `internal_key_schedule`.
+    #[cfg(feature = "cmac-prf")]
+    pub(crate) fn with_internal_key(key: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_key_schedule(internal_key_schedule(key)?))
+    }
+
+    /// Derive the subkeys for an expanded key.
+    fn from_key_schedule((expanded_key, nr): ([u8; 240], usize)) -> Self {
+        // L := AES(K, const_Zero)
+        let l = encrypt_block_expanded(&CONST_ZERO, &expanded_key, nr);
+        let (k1, k2) = subkeys_from_l(&l);
+
+        Self {
+            expanded_key,
+            nr,
+            subkeys: SubKeys { k1, k2 },
+        }
+    }
+
+    /// Compute the AES-CMAC of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message for which to compute the MAC.
+    ///
+    /// # Returns
+    ///
+    /// The 128-bit MAC.
+    pub fn mac(&self, message: impl AsRef<[u8]>) -> [u8; 16] {
+        let message = message.as_ref();
+
+        // Step 1, generating the subkeys K1 and K2, is done in `Cmac::new`.
+
+        // Step 2: Determine the number of blocks n and whether the last block
+        // is complete. As in RFC 4493, an empty message is treated as one
+        // incomplete block, so n >= 1 and the last block starts at
+        // 16 * (n - 1).
+        let (n, flag) = if message.is_empty() {
+            (1, false)
+        } else {
+            (message.len().div_ceil(16), message.len().is_multiple_of(16))
+        };
+        let last_block_start = 16 * (n - 1);
+
+        // Step 3 & 4: Prepare the last block (m_last) for MAC processing. It
+        // holds 0 to 16 bytes and is padded on the stack. `flag` is true
+        // exactly if the last block is complete.
+        debug_assert_eq!(flag, message.len() - last_block_start == 16);
+        let m_last = cmac_last_block(
+            &message[last_block_start..],
+            &self.subkeys.k1,
+            &self.subkeys.k2,
+        );
+
+        // Step 5 & 6: Perform the AES-CMAC algorithm.
+        let mut x = [0u8; 16];
+        for chunk in message[..last_block_start].chunks_exact(16) {
+            let mut block = [0u8; 16];
+            block.copy_from_slice(chunk);
+            x = xor(&x, &block); // XOR with each block
+            x = encrypt_block_expanded(&x, &self.expanded_key, self.nr); // Encrypt with AES
+        }
+
+        let y = xor(&m_last, &x); // XOR with the last block
+        encrypt_block_expanded(&y, &self.expanded_key, self.nr) // Final AES encryption
+    }
+}
+
+/// A message authentication code with a 128-bit tag, keyed at construction.
+///
+/// The trait allows verification code to be written independently of the MAC
+/// algorithm. `verify` has a default implementation that recomputes the tag
+/// and compares it in constant time.
+///
+/// The trait is implemented by `Cmac`, and by `CbcMac` and `Pmac` with the
+/// `mac` feature.
+pub trait Mac {
+    /// Compute the tag of a message.
+    fn mac(&self, message: &[u8]) -> [u8; 16];
+
+    /// Verify the tag of a message in constant time.
+    ///
+    /// Only full 16-byte tags are accepted; a tag of any other length never
+    /// verifies. Use `aes_cmac_verify` for truncated CMAC tags.
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        ct_eq(&self.mac(message), tag)
+    }
+}
+
+impl Mac for Cmac {
+    fn mac(&self, message: &[u8]) -> [u8; 16] {
+        Cmac::mac(self, message)
+    }
+}
+
+/// Overwrite the expanded key and the subkeys with zeros.
+///
+/// This allows wrapping a `Cmac` in `zeroize::Zeroizing` to wipe the key
+/// material when it is dropped, as the envelope functions do. The number of
+/// rounds is not secret and is kept.
+#[cfg(feature = "envelope")]
+impl zeroize::Zeroize for Cmac {
+    fn zeroize(&mut self) {
+        self.expanded_key.zeroize();
+        self.subkeys.k1.zeroize();
+        self.subkeys.k2.zeroize();
+    }
+}
+
+impl std::fmt::Debug for Cmac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cmac")
+            .field("expanded_key", &"<redacted>")
+            .field("nr", &self.nr)
+            .field("subkeys", &self.subkeys)
+            .finish()
+    }
 }
 
 /// Compute AES-CMAC for a given message using a specified key.
@@ -128,49 +379,466 @@ pub fn generate_subkey(key: &[u8]) -> Result<([u8; 16], [u8; 16]), Box<dyn Error
 /// # Errors
 ///
 /// Returns an error if any cryptographic operation fails.
-pub fn aes_cmac(message: &[u8], key: &[u8]) -> Result<[u8; 16], Box<dyn Error>> {
-    // Step 1: Generate the subkeys K1 and K2.
-    let (k1, k2) = generate_subkey(key)?;
+pub fn aes_cmac(
+    message: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    // The key is expanded once and the last block is padded on the stack, so
+    // no heap allocation takes place.
+    let cmac = Cmac::new(key)?;
 
-    // Step 2: Determine the number of blocks n.
-    let n = if message.is_empty() {
-        1
-    } else {
-        (message.len() + 15) / 16
-    };
-    let flag = message.len() % 16 == 0;
+    Ok(cmac.mac(message))
+}
+
+/// Verify an AES-CMAC tag of full or truncated length.
+///
+/// The full 128-bit MAC is computed and its leftmost `expected.len()` bytes
+/// are compared with `expected` in constant time, as permitted by NIST SP
+/// 800-38B, section 5.5. A single function thus covers full and truncated
+/// tags. See `aes_cmac_mic32` for the reduced security margin of short tags.
+///
+/// # Arguments
+///
+/// * `message` - The message to authenticate.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `expected` - The expected tag, 1 to 16 bytes long.
+///
+/// # Returns
+///
+/// A `Result` containing `true` if the tag matches and `false` otherwise, or
+/// an error if the tag length is outside 1 to 16 bytes or the key is invalid.
+pub fn aes_cmac_verify(
+    message: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    expected: &[u8],
+) -> Result<bool, Box<dyn Error>> {
+    if expected.is_empty() || expected.len() > 16 {
+        return Err(format!(
+            "AES CMAC ERROR: Expected tag must be 1 to 16 bytes, got {} bytes",
+            expected.len()
+        )
+        .into());
+    }
+
+    let mac = aes_cmac(message, key)?;
+
+    Ok(ct_eq(&mac[..expected.len()], expected))
+}
+
+/// Compute the AES-CMAC of a firmware image with a fixed length framing.
+///
+/// The MAC is computed over `LEN || image`, where `LEN` is a single 16-byte
+/// block holding the image length in bytes as a 128-bit big-endian integer.
+/// Binding the length into the first block removes any ambiguity between
+/// images of different sizes (e.g. truncated or extended images) and gives
+/// independent implementations a precise definition to agree on.
+///
+/// # Arguments
+///
+/// * `image` - The firmware image.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit tag if successful, or an error.
+pub fn firmware_mac(
+    image: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let image = image.as_ref();
+    let cmac = Cmac::new(key)?;
+
+    // Stream the length block and the image, so the image is not copied
+    let mut stream = CmacStream::new(&cmac);
+    stream.update(&(image.len() as u128).to_be_bytes());
+    stream.update(image);
+
+    Ok(stream.finalize())
+}
+
+/// Verify the tag of a firmware image computed with `firmware_mac`.
+///
+/// The tag comparison is performed in constant time.
+///
+/// # Arguments
+///
+/// * `image` - The firmware image.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `tag` - The expected 128-bit tag.
+///
+/// # Returns
+///
+/// A `Result` containing `true` if the tag matches and `false` otherwise, or
+/// an error if the MAC computation fails.
+pub fn verify_firmware_mac(
+    image: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    tag: &[u8; 16],
+) -> Result<bool, Box<dyn Error>> {
+    let computed = firmware_mac(image, key)?;
+
+    Ok(ct_eq(&computed, tag))
+}
+
+/// Compute the AES-CMAC over a sequence of fields with length framing.
+///
+/// The MAC is computed over
+///
+/// ```text
+/// len(f0) || f0 || len(f1) || f1 || ...
+/// ```
+///
+/// where each length is the field length in bytes as a 4-byte big-endian
+/// integer. Unlike MACing the plain concatenation, the framing keeps the field
+/// boundaries unambiguous, so e.g. `["ab", "c"]` and `["a", "bc"]` yield
+/// different tags.
+///
+/// # Arguments
+///
+/// * `fields` - The fields to authenticate, in order.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit tag if successful, or an error if the
+/// key is invalid or a field is longer than `u32::MAX` bytes.
+pub fn aes_cmac_fields(
+    fields: &[&[u8]],
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let cmac = Cmac::new(key)?;
+    let mut stream = CmacStream::new(&cmac);
+
+    for field in fields {
+        let len = u32::try_from(field.len()).map_err(|_| {
+            format!(
+                "AES CMAC ERROR: Field is too long, got {} bytes",
+                field.len()
+            )
+        })?;
+        stream.update(&len.to_be_bytes());
+        stream.update(field);
+    }
+
+    Ok(stream.finalize())
+}
+
+/// Compute a 256-bit authentication tag from two domain-separated AES-CMACs.
+///
+/// The tag is computed as
+///
+/// ```text
+/// AES-CMAC(key, 0x01 || message) || AES-CMAC(key, 0x02 || message)
+/// ```
+///
+/// The leading byte separates the two CMAC computations, so the halves are
+/// independent of each other. This is useful where a tag longer than 128 bits
+/// is desired. Note that the security of the tag is still bounded by the
+/// strength of AES-CMAC and the key size.
+///
+/// **Non-standard:** This construction is not specified by RFC 4493 or NIST
+/// SP 800-38B and will not interoperate with other implementations unless
+/// they implement the exact same construction.
+///
+/// # Arguments
+///
+/// * `message` - The message to authenticate.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 256-bit tag if successful, or an error.
+pub fn aes_cmac_256(
+    message: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 32], Box<dyn Error>> {
+    let message = message.as_ref();
+    let key = key.as_ref();
+
+    let mut input = Vec::with_capacity(1 + message.len());
+    input.push(0x01);
+    input.extend_from_slice(message);
+
+    let mut tag = [0u8; 32];
+    tag[..16].copy_from_slice(&aes_cmac(&input, key)?);
+
+    input[0] = 0x02;
+    tag[16..].copy_from_slice(&aes_cmac(&input, key)?);
+
+    Ok(tag)
+}
+
+/// Compute a 32-bit message integrity code (MIC) from the AES-CMAC.
+///
+/// The MIC is the leftmost 4 bytes of the full 128-bit AES-CMAC, as permitted
+/// by NIST SP 800-38B, section 5.5. Some constrained protocols use such short
+/// MICs to save bandwidth.
+///
+/// **Security note:** A 32-bit tag can be forged by guessing with a
+/// probability of 2^-32 per attempt. Only use it where the number of
+/// verification attempts under one key is strictly limited, e.g. by
+/// rate limiting or by discarding the key after a few failures.
+///
+/// # Arguments
+///
+/// * `message` - The message to authenticate.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 4-byte MIC if successful, or an error.
+pub fn aes_cmac_mic32(
+    message: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 4], Box<dyn Error>> {
+    let mac = aes_cmac(message, key)?;
+
+    let mut mic = [0u8; 4];
+    mic.copy_from_slice(&mac[..4]);
+
+    Ok(mic)
+}
+
+/// Verify a 32-bit MIC computed with `aes_cmac_mic32`.
+///
+/// The comparison is performed in constant time. See `aes_cmac_mic32` for the
+/// reduced security margin of 32-bit tags.
+///
+/// # Arguments
+///
+/// * `message` - The message to authenticate.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `mic` - The expected 4-byte MIC.
+///
+/// # Returns
+///
+/// A `Result` containing `true` if the MIC matches and `false` otherwise, or
+/// an error if the MAC computation fails.
+pub fn verify_mic32(
+    message: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    mic: &[u8; 4],
+) -> Result<bool, Box<dyn Error>> {
+    let computed = aes_cmac_mic32(message, key)?;
+
+    Ok(ct_eq(&computed, mic))
+}
+
+/// Fixed message authenticated by `key_check_tag`.
+pub const KEY_CHECK_MESSAGE: &[u8] = b"SOFT-AES-KEY-CHECK";
+
+/// Compute a key check tag to identify a key without revealing it.
+///
+/// The tag is the AES-CMAC of the fixed message `KEY_CHECK_MESSAGE` under
+/// `key`. Storing it alongside ciphertext allows rejecting a wrong key with
+/// `verify_key_check` before an expensive decryption is started.
+///
+/// # Arguments
+///
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit key check tag, or an error if the key
+/// length is invalid.
+pub fn key_check_tag(key: impl AsRef<[u8]>) -> Result<[u8; 16], Box<dyn Error>> {
+    aes_cmac(KEY_CHECK_MESSAGE, key)
+}
+
+/// Verify that `key` matches a key check tag computed with `key_check_tag`.
+///
+/// The tag comparison is performed in constant time.
+///
+/// # Arguments
+///
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `expected` - The stored key check tag.
+///
+/// # Returns
+///
+/// `true` if the key matches the tag, `false` otherwise or if the key length
+/// is invalid.
+pub fn verify_key_check(key: impl AsRef<[u8]>, expected: &[u8; 16]) -> bool {
+    match key_check_tag(key) {
+        Ok(tag) => ct_eq(&tag, expected),
+        Err(_) => false,
+    }
+}
+
+/// Compute the AES-CMAC of a file by memory-mapping it.
+///
+/// The file is mapped into memory and MACed in one pass without copying it
+/// into a buffer, which is the fastest way to authenticate very large files.
+/// If the file cannot be mapped (e.g. it is empty or the platform does not
+/// support mapping it), it is read into memory instead. The result is the same
+/// as calling `aes_cmac` on the file contents.
+///
+/// The file must not be modified by another process while it is mapped,
+/// otherwise the result is unspecified.
+///
+/// This function requires the `mmap` feature.
+///
+/// # Arguments
+///
+/// * `path` - Path to the file to authenticate.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit tag if successful, or an error if the
+/// file cannot be opened or read, or the key is invalid.
+#[cfg(feature = "mmap")]
+pub fn aes_cmac_mmap(
+    path: impl AsRef<std::path::Path>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let file = std::fs::File::open(path.as_ref())?;
+
+    // SAFETY: The mapping is only read while it is alive. Concurrent
+    // modification of the file is documented as unsupported.
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(map) => aes_cmac(&map[..], key),
+        Err(_) => aes_cmac(std::fs::read(path)?, key),
+    }
+}
+
+/// Compute the AES-CMAC of `prefix || [0u8; zero_len] || suffix`.
+///
+/// The zero run is fed to the CMAC chaining 16 bytes at a time from a fixed
+/// zero block, so it is never materialized in memory. This suits large sparse
+/// data such as disk images. The result is identical to `aes_cmac` over the
+/// concatenated data.
+///
+/// **Cost:** CMAC chains every block through the cipher, so the zero run
+/// cannot be skipped. Each 16 bytes of zeros still cost one AES block
+/// encryption, and the running time grows linearly with `zero_len`. Only the
+/// allocation is saved; memory use is constant. The key is expanded once.
+///
+/// # Arguments
+///
+/// * `prefix` - The data before the zero run.
+/// * `zero_len` - The number of zero bytes between `prefix` and `suffix`.
+/// * `suffix` - The data after the zero run.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit tag if successful, or an error if the
+/// key is invalid.
+pub fn aes_cmac_with_zero_run(
+    prefix: impl AsRef<[u8]>,
+    zero_len: usize,
+    suffix: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let cmac = Cmac::new(key)?;
+    let mut stream = CmacStream::new(&cmac);
+
+    stream.update(prefix.as_ref());
+    let mut remaining = zero_len;
+    while remaining > 0 {
+        let n = remaining.min(16);
+        stream.update(&CONST_ZERO[..n]);
+        remaining -= n;
+    }
+    stream.update(suffix.as_ref());
+
+    Ok(stream.finalize())
+}
+
+/// Incremental AES-CMAC over data supplied in pieces.
+///
+/// Up to one block is buffered. A full buffered block is only chained once
+/// more data follows, as the last block has to be combined with a subkey.
+struct CmacStream<'a> {
+    cmac: &'a Cmac,
+    x: [u8; 16],
+    buffer: [u8; 16],
+    buffer_len: usize,
+}
+
+impl<'a> CmacStream<'a> {
+    fn new(cmac: &'a Cmac) -> Self {
+        Self {
+            cmac,
+            x: [0u8; 16],
+            buffer: [0u8; 16],
+            buffer_len: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer_len == 16 {
+                self.x = xor(&self.x, &self.buffer);
+                self.x = encrypt_block_expanded(&self.x, &self.cmac.expanded_key, self.cmac.nr);
+                self.buffer_len = 0;
+            }
+
+            let n = (16 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&data[..n]);
+            self.buffer_len += n;
+            data = &data[n..];
+        }
+    }
+
+    fn finalize(self) -> [u8; 16] {
+        let m_last = cmac_last_block(
+            &self.buffer[..self.buffer_len],
+            &self.cmac.subkeys.k1,
+            &self.cmac.subkeys.k2,
+        );
+
+        let y = xor(&m_last, &self.x);
+        encrypt_block_expanded(&y, &self.cmac.expanded_key, self.cmac.nr)
+    }
+}
+
+/// Prepare the last message block M_last (RFC 4493, steps 3 and 4).
+///
+/// A complete block of 16 bytes is XORed with `k1`. An incomplete block,
+/// including the empty block of an empty message, is padded with `0x80`
+/// followed by zeros and XORed with `k2`.
+///
+/// # Panics
+///
+/// Panics if `last_chunk` is longer than 16 bytes.
+pub(crate) fn cmac_last_block(last_chunk: &[u8], k1: &[u8; 16], k2: &[u8; 16]) -> [u8; 16] {
+    assert!(
+        last_chunk.len() <= 16,
+        "AES CMAC ERROR: Last block must be at most 16 bytes, got {}",
+        last_chunk.len()
+    );
 
-    // Step 3 & 4: Prepare the last block (m_last) for MAC processing.
     let mut m_last = [0u8; 16];
-    if flag && !message.is_empty() {
-        // If the message is not empty and the last block is complete
-        m_last.copy_from_slice(&message[16 * (n - 1)..]);
-        xor_with_subkey(&mut m_last, &k1); // XOR with K1
+    m_last[..last_chunk.len()].copy_from_slice(last_chunk);
+
+    if last_chunk.len() == 16 {
+        xor_with_subkey(&mut m_last, k1); // XOR with K1
     } else {
-        // If the message is empty or the last block is not complete
-        let mut last_block = if message.is_empty() {
-            Vec::new()
-        } else {
-            message[16 * (n - 1)..].to_vec()
-        };
-        pad_80(&mut last_block, 16)?; // Apply the padding
-        m_last.copy_from_slice(&last_block);
-        xor_with_subkey(&mut m_last, &k2); // XOR with K2
+        // Apply 0x80 padding, the remaining bytes are already zero
+        m_last[last_chunk.len()] = 0x80;
+        xor_with_subkey(&mut m_last, k2); // XOR with K2
     }
 
-    // Step 5 & 6: Perform the AES-CMAC algorithm.
-    let mut x = [0u8; 16];
-    for i in 0..n - 1 {
-        let mut block = [0u8; 16];
-        block.copy_from_slice(&message[16 * i..16 * (i + 1)]);
-        x = xor(&x, &block); // XOR with each block
-        x = aes_enc_block(&x, key)?; // Encrypt with AES
+    m_last
+}
+
+/// Compare two byte slices in constant time.
+///
+/// The running time depends only on the lengths of the slices, not on their
+/// contents. Slices of different lengths are never equal.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
     }
 
-    let y = xor(&m_last, &x); // XOR with the last block
-    let t = aes_enc_block(&y, key)?; // Final AES encryption to produce the MAC
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
 
-    Ok(t)
+    std::hint::black_box(diff) == 0
 }
 
 /// Helper function to XOR a block with a subkey.
@@ -0,0 +1,121 @@
+//! AES-CMAC-PRF Implementation
+//!
+//! This module provides pseudorandom functions (PRF) based on AES-CMAC that
+//! accept keys of arbitrary length. A key that does not have the native length
+//! of the underlying cipher is first conditioned ("folded") to a key of the
+//! right size by computing a CMAC over it under an all-zero key.
+//!
+//! # Features
+//!
+//! - `aes_cmac_prf_128`: The AES-CMAC-PRF-128 algorithm as specified in
+//!   RFC 4615. Keys of any length are folded to a 128-bit key.
+//!
+//! - `aes_cmac_prf_256`: An analogous, non-standard variant based on AES-256
+//!   CMAC. Keys of any length are folded to a 256-bit key, so users that
+//!   standardized on 256-bit keys keep the full key strength.
+//!
+//! # Example
+//!
+//! Compute the AES-CMAC-PRF-128 for the RFC 4615 test vector with a 10-byte
+//! key:
+//!
+//! ```
+//! use crate::soft_aes::aes::aes_cmac_prf_128;
+//! use hex::decode as hex_decode;
+//!
+//! let key = hex_decode("00010203040506070809").unwrap();
+//! let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+//!
+//! let prv = aes_cmac_prf_128(&key, &message).unwrap();
+//!
+//! assert_eq!(
+//!     prv.to_vec(),
+//!     hex_decode("290d9e112edb09ee141fcf64c0b72f3d").unwrap()
+//! );
+//! ```
+//!
+//! # References
+//!
+//! - RFC 4615: The Advanced Encryption Standard-Cipher-based Message
+//!   Authentication Code-Pseudo-Random Function-128 (AES-CMAC-PRF-128)
+//!   Algorithm for the Internet Key Exchange Protocol (IKE)
+//!   [https://www.rfc-editor.org/rfc/rfc4615]
+//!
+//! # Disclaimer
+//!
+//! - `aes_cmac_prf_256` is not specified by RFC 4615 and does not interoperate
+//!   with AES-CMAC-PRF-128. Only use it where both sides agree on it.
+
+use super::aes_cmac::{aes_cmac, Cmac};
+use super::aes_core::{AES_128_KEY_SIZE, AES_256_KEY_SIZE};
+
+use std::error::Error;
+
+/// Compute AES-CMAC-PRF-128 as specified in RFC 4615.
+///
+/// If the key is exactly 16 bytes long it is used as is. Otherwise it is
+/// folded to a 128-bit key `K = AES-CMAC(0^128, key)`. The output is
+/// `AES-CMAC(K, message)`.
+///
+/// # Arguments
+///
+/// * `key` - The variable-length key, including the empty key.
+/// * `message` - The message to process.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit pseudorandom value, or an error.
+pub fn aes_cmac_prf_128(
+    key: impl AsRef<[u8]>,
+    message: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let key = key.as_ref();
+
+    if key.len() == AES_128_KEY_SIZE {
+        aes_cmac(message, key)
+    } else {
+        let folded_key = Cmac::with_internal_key(&[0u8; AES_128_KEY_SIZE])?.mac(key);
+        aes_cmac(message, folded_key)
+    }
+}
+
+/// Compute an AES-256 based analogue of AES-CMAC-PRF-128.
+///
+/// If the key is exactly 32 bytes long it is used as is, so the result equals
+/// the plain AES-256 CMAC of the message. Otherwise the key is folded to a
+/// 256-bit key `K = AES-CMAC(0^256, 0x01 || key) || AES-CMAC(0^256, 0x02 || key)`.
+/// The output is `AES-CMAC(K, message)`.
+///
+/// This construction is not part of RFC 4615.
+///
+/// # Arguments
+///
+/// * `key` - The variable-length key, including the empty key.
+/// * `message` - The message to process.
+///
+/// # Returns
+///
+/// A `Result` containing the 128-bit pseudorandom value, or an error.
+pub fn aes_cmac_prf_256(
+    key: impl AsRef<[u8]>,
+    message: impl AsRef<[u8]>,
+) -> Result<[u8; 16], Box<dyn Error>> {
+    let key = key.as_ref();
+
+    if key.len() == AES_256_KEY_SIZE {
+        return aes_cmac(message, key);
+    }
+
+    let zero_key = Cmac::with_internal_key(&[0u8; AES_256_KEY_SIZE])?;
+    let mut input = Vec::with_capacity(key.len() + 1);
+    let mut folded_key = [0u8; AES_256_KEY_SIZE];
+
+    input.push(0x01);
+    input.extend_from_slice(key);
+    folded_key[..16].copy_from_slice(&zero_key.mac(&input));
+
+    input[0] = 0x02;
+    folded_key[16..].copy_from_slice(&zero_key.mac(&input));
+
+    aes_cmac(message, folded_key)
+}
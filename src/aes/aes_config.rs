@@ -0,0 +1,216 @@
+//! AES Mode Configuration Builder
+//!
+//! This module provides a fluent builder over the ECB and CBC free functions
+//! of this crate. Instead of passing the key, IV and padding to every call, a
+//! configuration is assembled once, validated by `build`, and the resulting
+//! `Cipher` is used to encrypt and decrypt.
+//!
+//! # Features
+//!
+//! - `AesConfig`: Collects the key, mode of operation, IV and padding.
+//!   `AesConfig::build` checks that the configuration is complete and
+//!   consistent for the chosen mode.
+//!
+//! - `Cipher`: A validated configuration with `encrypt` and `decrypt`
+//!   methods. It calls the corresponding free functions internally, so the
+//!   output is identical to calling them directly.
+//!
+//! - `Mode` and `Padding`: The supported modes of operation and padding
+//!   schemes.
+//!
+//! # Example
+//!
+//! ```
+//! use crate::soft_aes::aes::{AesConfig, Mode, Padding};
+//!
+//! let cipher = AesConfig::new(b"Very secret key.")
+//!     .mode(Mode::Cbc)
+//!     .iv(b"Random Init Vec.")
+//!     .padding(Padding::Pkcs7)
+//!     .build()
+//!     .expect("Invalid configuration");
+//!
+//! let encrypted = cipher.encrypt(b"Example plaintext.").expect("Encryption failed");
+//! let decrypted = cipher.decrypt(&encrypted).expect("Decryption failed");
+//!
+//! assert_eq!(decrypted, b"Example plaintext.");
+//! ```
+//!
+//! # Disclaimer
+//!
+//! - A `Cipher` in CBC mode uses the same IV for every call to `encrypt`. The
+//!   IV must be unique for every message encrypted under the same key, so a
+//!   new `Cipher` has to be built for every message.
+
+use super::aes_cbc::{aes_dec_cbc, aes_enc_cbc};
+use super::aes_core::{num_rounds, AES_BLOCK_SIZE};
+use super::aes_ecb::{aes_dec_ecb, aes_enc_ecb};
+
+use std::error::Error;
+
+/// Mode of operation of a `Cipher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Electronic Codebook mode. Does not use an IV.
+    Ecb,
+    /// Cipher Block Chaining mode. Requires an IV.
+    Cbc,
+}
+
+/// Padding scheme of a `Cipher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    /// No padding. The data must be a multiple of `AES_BLOCK_SIZE`.
+    #[default]
+    None,
+    /// PKCS#7 padding.
+    Pkcs7,
+    /// 0x80 padding (ISO/IEC 9797-1 Padding Method 2).
+    Iso80,
+}
+
+impl Padding {
+    /// The padding argument of the free mode functions.
+    fn as_option(self) -> Option<&'static str> {
+        match self {
+            Padding::None => None,
+            Padding::Pkcs7 => Some("PKCS7"),
+            Padding::Iso80 => Some("0x80"),
+        }
+    }
+}
+
+/// Builder for a `Cipher`.
+///
+/// The mode must always be set; CBC mode additionally requires an IV. The
+/// padding defaults to `Padding::None`, like the free functions.
+#[derive(Clone)]
+pub struct AesConfig {
+    key: Vec<u8>,
+    mode: Option<Mode>,
+    iv: Option<[u8; AES_BLOCK_SIZE]>,
+    padding: Padding,
+}
+
+impl AesConfig {
+    /// Start a new configuration with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - AES-128, AES-192 or AES-256 key. The length is checked by
+    ///   `build`.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: key.as_ref().to_vec(),
+            mode: None,
+            iv: None,
+            padding: Padding::None,
+        }
+    }
+
+    /// Set the mode of operation.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Set the initialization vector for CBC mode.
+    pub fn iv(mut self, iv: &[u8; AES_BLOCK_SIZE]) -> Self {
+        self.iv = Some(*iv);
+        self
+    }
+
+    /// Set the padding scheme.
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Validate the configuration and build the `Cipher`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Cipher`, or an error if the key length is
+    /// invalid, no mode is set, CBC mode has no IV, or ECB mode has an IV.
+    pub fn build(self) -> Result<Cipher, Box<dyn Error>> {
+        num_rounds(&self.key)?;
+
+        let mode = self
+            .mode
+            .ok_or("AES CONFIG ERROR: Mode of operation is not set")?;
+
+        match (mode, self.iv) {
+            (Mode::Cbc, None) => return Err("AES CONFIG ERROR: CBC mode requires an IV".into()),
+            (Mode::Ecb, Some(_)) => {
+                return Err("AES CONFIG ERROR: ECB mode does not use an IV".into())
+            }
+            _ => {}
+        }
+
+        Ok(Cipher {
+            key: self.key,
+            mode,
+            iv: self.iv,
+            padding: self.padding,
+        })
+    }
+}
+
+/// A validated AES configuration created with `AesConfig::build`.
+#[derive(Clone)]
+pub struct Cipher {
+    key: Vec<u8>,
+    mode: Mode,
+    iv: Option<[u8; AES_BLOCK_SIZE]>,
+    padding: Padding,
+}
+
+impl Cipher {
+    /// The mode of operation.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The padding scheme.
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Encrypt data with the configured mode, IV and padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `plaintext` - The data to encrypt.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the ciphertext, or an error from the underlying
+    /// mode function.
+    pub fn encrypt(&self, plaintext: impl AsRef<[u8]>) -> Result<Vec<u8>, Box<dyn Error>> {
+        match (self.mode, &self.iv) {
+            (Mode::Cbc, Some(iv)) => {
+                aes_enc_cbc(plaintext, &self.key, iv, self.padding.as_option())
+            }
+            _ => aes_enc_ecb(plaintext, &self.key, self.padding.as_option()),
+        }
+    }
+
+    /// Decrypt data with the configured mode, IV and padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `ciphertext` - The data to decrypt.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the plaintext, or an error from the underlying
+    /// mode function.
+    pub fn decrypt(&self, ciphertext: impl AsRef<[u8]>) -> Result<Vec<u8>, Box<dyn Error>> {
+        match (self.mode, &self.iv) {
+            (Mode::Cbc, Some(iv)) => {
+                aes_dec_cbc(ciphertext, &self.key, iv, self.padding.as_option())
+            }
+            _ => aes_dec_ecb(ciphertext, &self.key, self.padding.as_option()),
+        }
+    }
+}
@@ -24,14 +24,39 @@
 //!   initial cipher key.
 //! - Provides functions for each step of the AES algorithm, including:
 //!     - `sub_bytes` and `inv_sub_bytes` for the SubBytes and InvSubBytes
-//!        steps (byte substitution).
+//!       steps (byte substitution).
 //!     - `shift_rows` and `inv_shift_rows` for the ShiftRows and InvShiftRows
-//!        steps.
+//!       steps.
 //!     - `mix_columns` and `inv_mix_columns` for the MixColumns and
-//!        InvMixColumns steps.
+//!       InvMixColumns steps.
 //!     - `add_round_key` for the AddRoundKey step.
 //! - Contains the main functions `aes_enc_block` and `aes_dec_block` for block
 //!   encryption and decryption.
+//! - With the `research` feature only: `aes_enc_block_with_sbox` and
+//!   `aes_dec_block_with_sbox` run a non-standard AES variant with a
+//!   caller-provided S-box for cryptanalysis experiments. Never use them to
+//!   protect data.
+//! - Provides the infallible `aes_enc_block_128`, `aes_enc_block_192` and
+//!   `aes_enc_block_256`, and their counterparts `aes_dec_block_128`,
+//!   `aes_dec_block_192` and `aes_dec_block_256`, for keys given as
+//!   fixed-size arrays.
+//! - Provides the `Block16` type to convert slices and vectors into a block
+//!   with a descriptive length error.
+//! - Provides the generic `aes_enc_block_typed` for keys implementing the
+//!   sealed `AesKeyBytes` trait.
+//! - Provides `aes_expand_key` and `aes_enc_block_expanded` to encrypt with
+//!   a key schedule expanded once or held by an external key store.
+//! - Provides `transpose_block` to convert blocks between the column-major
+//!   layout of the AES state and a row-major layout.
+//! - Provides `num_rounds` to query the number of rounds for a given key,
+//!   and the `const fn` helpers `is_valid_key_len` and
+//!   `num_rounds_for_key_len` for compile-time checks.
+//! - Provides `expanded_key_len` to query the size of the key schedule.
+//! - Provides `split_key` to split a combined key for two-key modes.
+//! - Provides `dump_round_keys` to print the key schedule for debugging, and
+//!   `expanded_key_hex` to export it as one hex string.
+//! - Exposes the low-level `aes_enc_state` and `aes_dec_state` functions that
+//!   run the cipher rounds directly on a state array (advanced / unstable).
 //!
 //! # Usage
 //!
@@ -85,6 +110,73 @@ use std::error::Error;
 // AES block size is fixed at 16 bytes
 pub const AES_BLOCK_SIZE: usize = 16;
 
+// The modes always pad with AES_BLOCK_SIZE. Check at compile time that it is
+// within the block sizes accepted by the padding functions (1 to 255), so the
+// block size error path of the padding functions is unreachable from the modes.
+const _: () = assert!(AES_BLOCK_SIZE > 0 && AES_BLOCK_SIZE < 256);
+
+/// A single 16-byte AES block.
+///
+/// `Block16` converts from byte slices and vectors of unknown length, e.g. an
+/// IV decoded from hex, with an error that names the length received instead
+/// of the opaque error of `<[u8; 16]>::try_from`.
+///
+/// # Example
+///
+/// ```
+/// use crate::soft_aes::aes::Block16;
+///
+/// let iv = Block16::try_from(vec![0u8; 16]).unwrap();
+/// assert_eq!(iv.0, [0u8; 16]);
+///
+/// let err = Block16::try_from(&[0u8; 15][..]).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "AES CORE ERROR: Invalid block length. Expected 16 bytes, got 15 bytes"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block16(pub [u8; AES_BLOCK_SIZE]);
+
+impl TryFrom<&[u8]> for Block16 {
+    type Error = Box<dyn Error>;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let block = <[u8; AES_BLOCK_SIZE]>::try_from(bytes).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES CORE ERROR: Invalid block length. Expected {} bytes, got {} bytes",
+                    AES_BLOCK_SIZE,
+                    bytes.len()
+                ),
+            )
+        })?;
+
+        Ok(Self(block))
+    }
+}
+
+impl TryFrom<Vec<u8>> for Block16 {
+    type Error = Box<dyn Error>;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl From<Block16> for [u8; AES_BLOCK_SIZE] {
+    fn from(block: Block16) -> Self {
+        block.0
+    }
+}
+
+impl AsRef<[u8]> for Block16 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// AES key size constants define the key sizes used in the AES algorithm for
 /// the three standard variations of AES.
 pub const AES_128_KEY_SIZE: usize = 16;
@@ -257,12 +349,12 @@ fn mul(a: u8, b: u8) -> u8 {
 /// # Parameters
 ///
 /// * `key`: A slice containing the initial AES key. Its length can be either
-///          16, 24, or 32 bytes, corresponding to AES-128, AES-192, and
-///          AES-256, respectively.
+///   16, 24, or 32 bytes, corresponding to AES-128, AES-192, and
+///   AES-256, respectively.
 /// * `nk`: The number of 4-byte words in the original key. This is 4 for
-///         AES-128, 6 for AES-192, and 8 for AES-256.
+///   AES-128, 6 for AES-192, and 8 for AES-256.
 /// * `nr`: The number of rounds in the AES cipher, which depends on the key
-///         size. This is 10 for AES-128, 12 for AES-192, and 14 for AES-256.
+///   size. This is 10 for AES-128, 12 for AES-192, and 14 for AES-256.
 ///
 /// # Returns
 ///
@@ -279,6 +371,14 @@ fn mul(a: u8, b: u8) -> u8 {
 /// certain implementations, as it avoids the overhead of multi-dimensional
 /// array indexing.
 fn expand_key(key: &[u8], nk: usize, nr: usize) -> [u8; 240] {
+    expand_key_with_sbox(key, nk, nr, &S_BOX)
+}
+
+/// Perform the key expansion with the given S-box used in SubWord.
+///
+/// This is the implementation of `expand_key`, which passes the standard
+/// S-box.
+fn expand_key_with_sbox(key: &[u8], nk: usize, nr: usize, s_box: &[u8; 256]) -> [u8; 240] {
     let mut expanded_key = [0u8; 240]; // Fixed buffer for expanded key
     let mut temp = [0u8; 4]; // Temporary storage for key schedule
 
@@ -295,7 +395,7 @@ fn expand_key(key: &[u8], nk: usize, nr: usize) -> [u8; 240] {
             temp[j] = expanded_key[(i - 1) * 4 + j];
         }
 
-        if i % nk == 0 {
+        if i.is_multiple_of(nk) {
             // Perform the RotWord operation for the first word in each new key
             let k = temp[0];
             temp.rotate_left(1); // Rotate the 4 bytes of the word to the left
@@ -303,7 +403,7 @@ fn expand_key(key: &[u8], nk: usize, nr: usize) -> [u8; 240] {
 
             // SubWord operation: Substitute each byte in `temp` using the S-Box
             for j in 0..4 {
-                temp[j] = S_BOX[temp[j] as usize];
+                temp[j] = s_box[temp[j] as usize];
             }
 
             // XOR the first byte of `temp` with the round constant (RCON)
@@ -311,7 +411,7 @@ fn expand_key(key: &[u8], nk: usize, nr: usize) -> [u8; 240] {
         } else if nk > 6 && i % nk == 4 {
             // For AES-256, apply SubWord operation every fourth word
             for j in 0..4 {
-                temp[j] = S_BOX[temp[j] as usize];
+                temp[j] = s_box[temp[j] as usize];
             }
         }
 
@@ -356,14 +456,15 @@ fn add_round_key(round: usize, state: &mut [[u8; 4]; 4], expanded_key: &[u8; 240
 /// # Parameters
 ///
 /// * `state`: The current state of the cipher, represented as a mut 2D array.
+/// * `s_box`: The S-box, `S_BOX` for standard AES.
 ///
 /// # Note
 ///
 /// The state is modified in place with the values from the S-box.
-fn sub_bytes(state: &mut [[u8; 4]; 4]) {
+fn sub_bytes(state: &mut [[u8; 4]; 4], s_box: &[u8; 256]) {
     for i in 0..4 {
         for j in 0..4 {
-            state[i][j] = S_BOX[state[i][j] as usize];
+            state[i][j] = s_box[state[i][j] as usize];
         }
     }
 }
@@ -379,14 +480,15 @@ fn sub_bytes(state: &mut [[u8; 4]; 4]) {
 /// # Parameters
 ///
 /// * `state`: The current state of the cipher, represented as a mut 2D array.
+/// * `inv_s_box`: The inverse S-box, `INV_S_BOX` for standard AES.
 ///
 /// # Note
 ///
 /// The state is modified in place with the values from the inverse S-box.
-fn inv_sub_bytes(state: &mut [[u8; 4]; 4]) {
+fn inv_sub_bytes(state: &mut [[u8; 4]; 4], inv_s_box: &[u8; 256]) {
     for i in 0..4 {
         for j in 0..4 {
-            state[i][j] = INV_S_BOX[state[i][j] as usize];
+            state[i][j] = inv_s_box[state[i][j] as usize];
         }
     }
 }
@@ -412,13 +514,9 @@ fn shift_rows(state: &mut [[u8; 4]; 4]) {
     state[1][3] = temp;
 
     // Rotate the third row 2 columns to the left
-    let temp = state[2][0];
-    state[2][0] = state[2][2];
-    state[2][2] = temp;
+    state[2].swap(0, 2);
 
-    let temp = state[2][1];
-    state[2][1] = state[2][3];
-    state[2][3] = temp;
+    state[2].swap(1, 3);
 
     // Rotate the fourth row 3 columns to the left
     let temp = state[3][0];
@@ -449,13 +547,9 @@ fn inv_shift_rows(state: &mut [[u8; 4]; 4]) {
     state[1][0] = temp;
 
     // Rotate second row 2 columns to right
-    let temp = state[2][0];
-    state[2][0] = state[2][2];
-    state[2][2] = temp;
+    state[2].swap(0, 2);
 
-    let temp = state[2][1];
-    state[2][1] = state[2][3];
-    state[2][3] = temp;
+    state[2].swap(1, 3);
 
     // Rotate third row 3 columns to right
     let temp = state[3][0];
@@ -477,6 +571,7 @@ fn inv_shift_rows(state: &mut [[u8; 4]; 4]) {
 /// # Note
 ///
 /// The state is modified in place with each column mixed accordingly.
+#[allow(clippy::needless_range_loop)]
 fn mix_columns(state: &mut [[u8; 4]; 4]) {
     for i in 0..4 {
         // Iterate over each column
@@ -514,6 +609,7 @@ fn mix_columns(state: &mut [[u8; 4]; 4]) {
 /// # Note
 ///
 /// The state is modified in place with each column mixed accordingly.
+#[allow(clippy::needless_range_loop)]
 fn inv_mix_columns(state: &mut [[u8; 4]; 4]) {
     for i in 0..4 {
         // Save original state for column i
@@ -572,6 +668,44 @@ fn copy_state_to_block(state: &[[u8; 4]; 4]) -> [u8; AES_BLOCK_SIZE] {
     block
 }
 
+/// Convert a block between column-major and row-major byte order.
+///
+/// AES maps a block to the state column by column: byte `i` of the block is
+/// `state[i % 4][i / 4]`. Some reference code and FFI interfaces instead
+/// serialize the state row by row. This function converts between the two
+/// layouts by transposing the 4x4 matrix, so intermediate values can be
+/// compared across tools. The transposition is its own inverse.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte block in one layout.
+///
+/// # Returns
+///
+/// The 16-byte block in the other layout.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::transpose_block;
+///
+/// let block: [u8; 16] = std::array::from_fn(|i| i as u8);
+/// let transposed = transpose_block(&block);
+///
+/// assert_eq!(transposed[..4], [0x00, 0x04, 0x08, 0x0c]);
+/// assert_eq!(transpose_block(&transposed), block);
+/// ```
+pub fn transpose_block(block: &[u8; AES_BLOCK_SIZE]) -> [u8; AES_BLOCK_SIZE] {
+    let state = copy_block_to_state(block);
+
+    let mut transposed = [0u8; AES_BLOCK_SIZE];
+    for (row, bytes) in state.iter().enumerate() {
+        transposed[row * 4..row * 4 + 4].copy_from_slice(bytes);
+    }
+
+    transposed
+}
+
 /// Calculate the number of 32-bit words in the key and the number of
 /// encryption rounds based on the key length for AES encryption.
 ///
@@ -618,18 +752,445 @@ fn calculate_parameters(key_length_bytes: usize) -> (usize, usize) {
 /// * `Ok(())` - If the block and key are of valid lengths.
 /// * `Err(Box<dyn Error>)` - If the key length is invalid.
 fn validate_key_len(key_len: usize) -> Result<(), Box<dyn Error>> {
+    if is_valid_key_len(key_len) {
+        return Ok(());
+    }
+
+    Err(Box::new(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!(
+            "AES CORE ERROR: Invalid key length. Expected 16, 24, or 32 bytes, got {} bytes",
+            key_len,
+        ),
+    )))
+}
+
+/// Check whether a length in bytes is a valid AES key length.
+///
+/// This is the predicate used by all functions that validate a key, as a
+/// `const fn`, so key sizes can be checked at compile time:
+///
+/// ```
+/// use soft_aes::aes::is_valid_key_len;
+///
+/// const KEY_LEN: usize = 24;
+/// const _: () = assert!(is_valid_key_len(KEY_LEN));
+/// ```
+///
+/// # Parameters
+///
+/// * `key_len`: Length of the cipher key in bytes.
+///
+/// # Returns
+///
+/// `true` for 16, 24, and 32 bytes, `false` otherwise.
+pub const fn is_valid_key_len(key_len: usize) -> bool {
+    matches!(
+        key_len,
+        AES_128_KEY_SIZE | AES_192_KEY_SIZE | AES_256_KEY_SIZE
+    )
+}
+
+/// Return the number of rounds for a key length, in a `const` context.
+///
+/// This is the `const fn` counterpart of `num_rounds`, taking the key length
+/// instead of the key.
+///
+/// # Parameters
+///
+/// * `key_len`: Length of the cipher key in bytes.
+///
+/// # Returns
+///
+/// `Some(10)`, `Some(12)`, or `Some(14)` for AES-128, AES-192, and AES-256
+/// key lengths, or `None` if the key length is invalid.
+pub const fn num_rounds_for_key_len(key_len: usize) -> Option<usize> {
     match key_len {
-        AES_128_KEY_SIZE | AES_192_KEY_SIZE | AES_256_KEY_SIZE => Ok(()),
-        _ => Err(Box::new(std::io::Error::new(
+        AES_128_KEY_SIZE => Some(10),
+        AES_192_KEY_SIZE => Some(12),
+        AES_256_KEY_SIZE => Some(14),
+        _ => None,
+    }
+}
+
+/// Return the number of rounds used by AES for the given key.
+///
+/// The number of rounds depends on the key size: 10 for AES-128, 12 for
+/// AES-192, and 14 for AES-256.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key. Only its length is inspected.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of rounds for the key size.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub fn num_rounds(key: &[u8]) -> Result<usize, Box<dyn Error>> {
+    validate_key_len(key.len())?;
+
+    let (_, nr) = calculate_parameters(key.len());
+
+    Ok(nr)
+}
+
+/// Return the length of the expanded key for the given key length.
+///
+/// The key schedule holds `nr + 1` round keys of `AES_BLOCK_SIZE` bytes each,
+/// i.e. 176 bytes for AES-128, 208 bytes for AES-192, and 240 bytes for
+/// AES-256. Internally the schedule is always stored in a 240-byte buffer;
+/// this function gives the number of bytes actually used, e.g. to size
+/// buffers exchanged over FFI.
+///
+/// # Parameters
+///
+/// * `key_len`: The length of the cipher key in bytes.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The length of the expanded key in bytes.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub fn expanded_key_len(key_len: usize) -> Result<usize, Box<dyn Error>> {
+    validate_key_len(key_len)?;
+
+    let (_, nr) = calculate_parameters(key_len);
+
+    Ok(AES_BLOCK_SIZE * (nr + 1))
+}
+
+/// Split a combined key into two AES keys of equal length.
+///
+/// Two-key constructions such as XTS, SIV or encrypt-then-MAC take a single
+/// input key that holds both keys back to back. This function standardizes
+/// the split and its validation.
+///
+/// # Parameters
+///
+/// * `key`: The combined key, 32, 48, or 64 bytes long.
+///
+/// # Returns
+///
+/// * `Ok((&[u8], &[u8]))` - The first and second half of the key, each 16,
+///   24, or 32 bytes long.
+/// * `Err(Box<dyn Error>)` - If the length is not twice a valid AES key size.
+#[allow(clippy::type_complexity)]
+pub fn split_key(key: &[u8]) -> Result<(&[u8], &[u8]), Box<dyn Error>> {
+    match key.len() {
+        32 | 48 | 64 => Ok(key.split_at(key.len() / 2)),
+        key_len => Err(Box::new(std::io::Error::new(
             std::io::ErrorKind::InvalidInput,
             format!(
-                "AES CORE ERROR: Invalid key length. Expected 16, 24, or 32 bytes, got {} bytes",
+                "AES CORE ERROR: Invalid combined key length. Expected 32, 48, or 64 bytes, got {} bytes",
                 key_len,
             ),
         ))),
     }
 }
 
+/// Write the round keys of an expanded key as hex lines for debugging.
+///
+/// The key is expanded and each 16-byte round key is written on its own line
+/// in the format `Round 0: 2b7e151628aed2a6abf7158809cf4f3c`. This makes it
+/// easy to compare the key schedule against reference implementations or the
+/// expansions listed in FIPS-197, Appendix A.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+/// * `out`: The writer that receives the `nr + 1` lines.
+///
+/// # Returns
+///
+/// * `Ok(())` - If all round keys were written.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid or writing fails.
+pub fn dump_round_keys(key: &[u8], out: &mut impl std::fmt::Write) -> Result<(), Box<dyn Error>> {
+    let (expanded_key, nr) = key_schedule(key)?;
+
+    for (round, round_key) in expanded_key.chunks_exact(16).take(nr + 1).enumerate() {
+        write!(out, "Round {}: ", round)?;
+        for byte in round_key {
+            write!(out, "{:02x}", byte)?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Return the key schedule of a key as a single lowercase hex string.
+///
+/// The `nr + 1` round keys are concatenated in round key order, so the string
+/// has `32 * (nr + 1)` characters. It can be pasted into reference tools, such
+/// as CrypTool's AES step-by-step page, to verify the key expansion.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The hex encoded key schedule.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub fn expanded_key_hex(key: &[u8]) -> Result<String, Box<dyn Error>> {
+    use std::fmt::Write;
+
+    let (expanded_key, nr) = key_schedule(key)?;
+
+    let mut hex = String::with_capacity(32 * (nr + 1));
+    for byte in &expanded_key[..16 * (nr + 1)] {
+        write!(hex, "{:02x}", byte)?;
+    }
+
+    Ok(hex)
+}
+
+/// Validate the key and expand it into the round key buffer.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+///
+/// # Returns
+///
+/// * `Ok(([u8; 240], usize))` - The expanded key and the number of rounds.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub(crate) fn key_schedule(key: &[u8]) -> Result<([u8; 240], usize), Box<dyn Error>> {
+    let schedule = internal_key_schedule(key)?;
+
+    #[cfg(feature = "weak-key-check")]
+    super::aes_weak_key::warn_weak_key(key);
+
+    Ok(schedule)
+}
+
+/// Validate the key and expand it without the weak key check.
+///
+/// This is `key_schedule` for keys that are fixed by an algorithm instead of
+/// passed in by the caller, such as the all-zero key of the CTR_DRBG
+/// instantiation. Checking them would report a weak key on every use.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+///
+/// # Returns
+///
+/// * `Ok(([u8; 240], usize))` - The expanded key and the number of rounds.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub(crate) fn internal_key_schedule(key: &[u8]) -> Result<([u8; 240], usize), Box<dyn Error>> {
+    validate_key_len(key.len())?;
+
+    let (nk, nr) = calculate_parameters(key.len());
+
+    Ok((expand_key(key, nk, nr), nr))
+}
+
+/// Encrypt a state array in place using an expanded key.
+///
+/// This is the AES cipher (FIPS-197, section 5.1) without the conversion
+/// between a 16-byte block and the 4x4 state. It is meant for custom
+/// constructions built on the AES round function that already operate on
+/// state arrays. The state is indexed as `state[row][column]`, so byte `i` of
+/// a block maps to `state[i % 4][i / 4]`.
+///
+/// **Advanced / unstable:** This is a low-level building block. No validation
+/// is performed on the expanded key or the number of rounds, and its signature
+/// may change in future releases. Prefer `aes_enc_block` where possible.
+///
+/// # Parameters
+///
+/// * `state`: The state to encrypt, modified in place.
+/// * `expanded_key`: The expanded key buffer containing all round keys, as
+///   produced by the AES key expansion.
+/// * `nr`: The number of rounds matching the expanded key: 10 for AES-128,
+///   12 for AES-192 and 14 for AES-256.
+///
+/// # Panics
+///
+/// Panics if `nr` is greater than 14.
+pub fn aes_enc_state(state: &mut [[u8; 4]; 4], expanded_key: &[u8; 240], nr: usize) {
+    encrypt_state_with_sbox(state, expanded_key, nr, &S_BOX);
+}
+
+/// Run the cipher rounds with the given S-box.
+///
+/// This is the implementation of `aes_enc_state`, which passes the standard
+/// S-box.
+fn encrypt_state_with_sbox(
+    state: &mut [[u8; 4]; 4],
+    expanded_key: &[u8; 240],
+    nr: usize,
+    s_box: &[u8; 256],
+) {
+    // Add the first round key to the state before starting the rounds
+    add_round_key(0, state, expanded_key);
+
+    // Main rounds
+    for round in 1..nr {
+        sub_bytes(state, s_box);
+        shift_rows(state);
+        mix_columns(state);
+        add_round_key(round, state, expanded_key);
+    }
+
+    // Final round (without mix_columns)
+    sub_bytes(state, s_box);
+    shift_rows(state);
+    add_round_key(nr, state, expanded_key);
+}
+
+/// Decrypt a state array in place using an expanded key.
+///
+/// This is the AES inverse cipher (FIPS-197, section 5.3) without the
+/// conversion between a 16-byte block and the 4x4 state. It is the counterpart
+/// of `aes_enc_state` and uses the same state layout.
+///
+/// **Advanced / unstable:** This is a low-level building block. No validation
+/// is performed on the expanded key or the number of rounds, and its signature
+/// may change in future releases. Prefer `aes_dec_block` where possible.
+///
+/// # Parameters
+///
+/// * `state`: The state to decrypt, modified in place.
+/// * `expanded_key`: The expanded key buffer containing all round keys, as
+///   produced by the AES key expansion.
+/// * `nr`: The number of rounds matching the expanded key: 10 for AES-128,
+///   12 for AES-192 and 14 for AES-256.
+///
+/// # Panics
+///
+/// Panics if `nr` is greater than 14.
+pub fn aes_dec_state(state: &mut [[u8; 4]; 4], expanded_key: &[u8; 240], nr: usize) {
+    decrypt_state_with_sbox(state, expanded_key, nr, &INV_S_BOX);
+}
+
+/// Run the inverse cipher rounds with the given inverse S-box.
+///
+/// This is the implementation of `aes_dec_state`, which passes the standard
+/// inverse S-box.
+fn decrypt_state_with_sbox(
+    state: &mut [[u8; 4]; 4],
+    expanded_key: &[u8; 240],
+    nr: usize,
+    inv_s_box: &[u8; 256],
+) {
+    // Add the last round key to the state before starting the rounds
+    add_round_key(nr, state, expanded_key);
+
+    // Main rounds
+    for round in (1..nr).rev() {
+        inv_shift_rows(state);
+        inv_sub_bytes(state, inv_s_box);
+        add_round_key(round, state, expanded_key);
+        inv_mix_columns(state);
+    }
+
+    // Final round (without inv_mix_columns)
+    inv_shift_rows(state);
+    inv_sub_bytes(state, inv_s_box);
+    add_round_key(0, state, expanded_key);
+}
+
+/// Encrypt a single block with an already expanded key.
+///
+/// The expanded key and the number of rounds must come from `key_schedule`.
+pub(crate) fn encrypt_block_expanded(
+    block: &[u8; AES_BLOCK_SIZE],
+    expanded_key: &[u8; 240],
+    nr: usize,
+) -> [u8; AES_BLOCK_SIZE] {
+    let mut state = copy_block_to_state(block);
+    aes_enc_state(&mut state, expanded_key, nr);
+    copy_state_to_block(&state)
+}
+
+/// Decrypt a single block with an already expanded key.
+///
+/// The expanded key and the number of rounds must come from `key_schedule`.
+pub(crate) fn decrypt_block_expanded(
+    block: &[u8; AES_BLOCK_SIZE],
+    expanded_key: &[u8; 240],
+    nr: usize,
+) -> [u8; AES_BLOCK_SIZE] {
+    let mut state = copy_block_to_state(block);
+    aes_dec_state(&mut state, expanded_key, nr);
+    copy_state_to_block(&state)
+}
+
+/// Expand a key into its key schedule.
+///
+/// This runs the AES key expansion (FIPS-197, section 5.2) once, so the
+/// schedule can be stored, e.g. in an external key store, and passed to
+/// `aes_enc_block_expanded` for every block.
+///
+/// # Parameters
+///
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+///
+/// # Returns
+///
+/// * `Ok(([u8; 240], usize))` - The expanded key and the number of rounds.
+///   Only the first `expanded_key_len` bytes of the buffer are used.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid.
+pub fn aes_expand_key(key: &[u8]) -> Result<([u8; 240], usize), Box<dyn Error>> {
+    key_schedule(key)
+}
+
+/// Encrypt a single block with an already expanded key.
+///
+/// This skips the key expansion of `aes_enc_block` for callers that already
+/// hold the key schedule, e.g. from `aes_expand_key` or an external key
+/// store.
+///
+/// **Advanced:** The expanded key is trusted as is. A schedule that was not
+/// produced by the AES key expansion, or that belongs to a different number of
+/// rounds, silently yields a wrong ciphertext. Prefer `aes_enc_block` where
+/// possible.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `expanded_key`: The expanded key buffer containing all round keys.
+/// * `nr`: The number of rounds matching the expanded key: 10 for AES-128,
+///   12 for AES-192 and 14 for AES-256.
+///
+/// # Returns
+///
+/// The 16-byte ciphertext block.
+///
+/// # Panics
+///
+/// Panics if `nr` is not 10, 12, or 14.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::{aes_enc_block, aes_enc_block_expanded, aes_expand_key};
+///
+/// let key = [0x2bu8; 16];
+/// let block = [0u8; 16];
+///
+/// let (expanded_key, nr) = aes_expand_key(&key).unwrap();
+///
+/// assert_eq!(
+///     aes_enc_block_expanded(&block, &expanded_key, nr),
+///     aes_enc_block(&block, &key).unwrap()
+/// );
+/// ```
+pub fn aes_enc_block_expanded(
+    block: &[u8; AES_BLOCK_SIZE],
+    expanded_key: &[u8; 240],
+    nr: usize,
+) -> [u8; AES_BLOCK_SIZE] {
+    assert!(
+        matches!(nr, 10 | 12 | 14),
+        "AES CORE ERROR: Invalid number of rounds. Expected 10, 12, or 14, got {}",
+        nr
+    );
+
+    encrypt_block_expanded(block, expanded_key, nr)
+}
+
 /// Encrypt a single block using the AES algorithm.
 ///
 /// This function handles AES encryption for a single block of data using the
@@ -639,15 +1200,15 @@ fn validate_key_len(key_len: usize) -> Result<(), Box<dyn Error>> {
 /// # Parameters
 ///
 /// * `block`: A reference to a 16-byte array representing the plaintext block
-///            to be encrypted.
+///   to be encrypted.
 /// * `key`: A reference to a byte slice representing the encryption key. The
-///          length of this slice determines the key size: 16 bytes for AES-128,
-///          24 bytes for AES-192, and 32 bytes for AES-256.
+///   length of this slice determines the key size: 16 bytes for AES-128,
+///   24 bytes for AES-192, and 32 bytes for AES-256.
 ///
 /// # Returns
 ///
 /// * `Ok([u8; AES_BLOCK_SIZE])` - A 16-byte array representing the encrypted
-///    ciphertext block.
+///   ciphertext block.
 /// * `Err(Box<dyn Error>)` - If the key length is invalid.
 ///
 /// # Errors
@@ -658,33 +1219,9 @@ pub fn aes_enc_block(
     block: &[u8; AES_BLOCK_SIZE],
     key: &[u8],
 ) -> Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>> {
-    let key_len = key.len();
-
-    validate_key_len(key_len)?;
-
-    let (nk, nr) = calculate_parameters(key_len);
-
-    let mut state = copy_block_to_state(block);
-
-    let expanded_key = expand_key(key, nk, nr);
-
-    // Add the first round key to the state before starting the rounds
-    add_round_key(0, &mut state, &expanded_key);
-
-    // Main rounds
-    for round in 1..nr {
-        sub_bytes(&mut state);
-        shift_rows(&mut state);
-        mix_columns(&mut state);
-        add_round_key(round, &mut state, &expanded_key);
-    }
+    let (expanded_key, nr) = key_schedule(key)?;
 
-    // Final round (without mix_columns)
-    sub_bytes(&mut state);
-    shift_rows(&mut state);
-    add_round_key(nr, &mut state, &expanded_key);
-
-    Ok(copy_state_to_block(&state))
+    Ok(encrypt_block_expanded(block, &expanded_key, nr))
 }
 
 /// Decrypt a single block using the AES algorithm.
@@ -696,15 +1233,15 @@ pub fn aes_enc_block(
 /// # Parameters
 ///
 /// * `ciphertext`: A reference to a 16-byte array representing the encrypted
-///                 block to be decrypted.
+///   block to be decrypted.
 /// * `key`: A reference to a byte slice representing the decryption key. The
-///          length of this slice determines the key size: 16 bytes for AES-128,
-///          24 bytes for AES-192, and 32 bytes for AES-256.
+///   length of this slice determines the key size: 16 bytes for AES-128,
+///   24 bytes for AES-192, and 32 bytes for AES-256.
 ///
 /// # Returns
 ///
 /// * `Ok([u8; AES_BLOCK_SIZE])` - A 16-byte array representing the decrypted
-///    plaintext block.
+///   plaintext block.
 /// * `Err(Box<dyn Error>)` - If the key length is invalid.
 ///
 /// # Errors
@@ -715,31 +1252,459 @@ pub fn aes_dec_block(
     ciphertext: &[u8; AES_BLOCK_SIZE],
     key: &[u8],
 ) -> Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>> {
-    let key_len = key.len();
+    let (expanded_key, nr) = key_schedule(key)?;
 
-    validate_key_len(key_len)?;
+    Ok(decrypt_block_expanded(ciphertext, &expanded_key, nr))
+}
 
-    let (nk, nr) = calculate_parameters(key_len);
+/// Encrypt a single block with a fixed-size AES-128 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_enc_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `key`: The 16-byte AES-128 key.
+///
+/// # Returns
+///
+/// The 16-byte ciphertext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_enc_block_128;
+///
+/// // FIPS-197, Appendix C.1
+/// let key: [u8; 16] = std::array::from_fn(|i| i as u8);
+/// let plaintext: [u8; 16] = std::array::from_fn(|i| i as u8 * 0x11);
+///
+/// let ciphertext = aes_enc_block_128(&plaintext, &key);
+/// assert_eq!(
+///     ciphertext,
+///     [
+///         0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30,
+///         0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a,
+///     ]
+/// );
+/// ```
+pub fn aes_enc_block_128(
+    block: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_128_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    encrypt_block_expanded(block, &expanded_key, nr)
+}
 
-    let mut state = copy_block_to_state(ciphertext);
+/// Encrypt a single block with a fixed-size AES-192 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_enc_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `key`: The 24-byte AES-192 key.
+///
+/// # Returns
+///
+/// The 16-byte ciphertext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_enc_block_192;
+///
+/// // FIPS-197, Appendix C.2
+/// let key: [u8; 24] = std::array::from_fn(|i| i as u8);
+/// let plaintext: [u8; 16] = std::array::from_fn(|i| i as u8 * 0x11);
+///
+/// let ciphertext = aes_enc_block_192(&plaintext, &key);
+/// assert_eq!(
+///     ciphertext,
+///     [
+///         0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0,
+///         0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91,
+///     ]
+/// );
+/// ```
+pub fn aes_enc_block_192(
+    block: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_192_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    encrypt_block_expanded(block, &expanded_key, nr)
+}
 
-    let expanded_key = expand_key(key, nk, nr);
+/// Encrypt a single block with a fixed-size AES-256 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_enc_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `key`: The 32-byte AES-256 key.
+///
+/// # Returns
+///
+/// The 16-byte ciphertext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_enc_block_256;
+///
+/// // FIPS-197, Appendix C.3
+/// let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+/// let plaintext: [u8; 16] = std::array::from_fn(|i| i as u8 * 0x11);
+///
+/// let ciphertext = aes_enc_block_256(&plaintext, &key);
+/// assert_eq!(
+///     ciphertext,
+///     [
+///         0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf,
+///         0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89,
+///     ]
+/// );
+/// ```
+pub fn aes_enc_block_256(
+    block: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_256_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    encrypt_block_expanded(block, &expanded_key, nr)
+}
 
-    // Add the last round key to the state before starting the rounds
-    add_round_key(nr, &mut state, &expanded_key);
+/// Decrypt a single block with a fixed-size AES-128 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_dec_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `ciphertext`: The 16-byte ciphertext block.
+/// * `key`: The 16-byte AES-128 key.
+///
+/// # Returns
+///
+/// The 16-byte plaintext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_dec_block_128;
+///
+/// // FIPS-197, Appendix C.1
+/// let key: [u8; 16] = std::array::from_fn(|i| i as u8);
+/// let ciphertext = [
+///     0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30,
+///     0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a,
+/// ];
+///
+/// let plaintext = aes_dec_block_128(&ciphertext, &key);
+/// assert_eq!(plaintext, std::array::from_fn(|i| i as u8 * 0x11));
+/// ```
+pub fn aes_dec_block_128(
+    ciphertext: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_128_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    decrypt_block_expanded(ciphertext, &expanded_key, nr)
+}
 
-    // Main rounds
-    for round in (1..nr).rev() {
-        inv_shift_rows(&mut state);
-        inv_sub_bytes(&mut state);
-        add_round_key(round, &mut state, &expanded_key);
-        inv_mix_columns(&mut state);
+/// Decrypt a single block with a fixed-size AES-192 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_dec_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `ciphertext`: The 16-byte ciphertext block.
+/// * `key`: The 24-byte AES-192 key.
+///
+/// # Returns
+///
+/// The 16-byte plaintext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_dec_block_192;
+///
+/// // FIPS-197, Appendix C.2
+/// let key: [u8; 24] = std::array::from_fn(|i| i as u8);
+/// let ciphertext = [
+///     0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0,
+///     0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91,
+/// ];
+///
+/// let plaintext = aes_dec_block_192(&ciphertext, &key);
+/// assert_eq!(plaintext, std::array::from_fn(|i| i as u8 * 0x11));
+/// ```
+pub fn aes_dec_block_192(
+    ciphertext: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_192_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    decrypt_block_expanded(ciphertext, &expanded_key, nr)
+}
+
+/// Decrypt a single block with a fixed-size AES-256 key.
+///
+/// The key length is guaranteed by the type, so no validation is performed
+/// and the function cannot fail. The output is identical to `aes_dec_block`
+/// with the same key.
+///
+/// # Parameters
+///
+/// * `ciphertext`: The 16-byte ciphertext block.
+/// * `key`: The 32-byte AES-256 key.
+///
+/// # Returns
+///
+/// The 16-byte plaintext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::aes_dec_block_256;
+///
+/// // FIPS-197, Appendix C.3
+/// let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+/// let ciphertext = [
+///     0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf,
+///     0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89,
+/// ];
+///
+/// let plaintext = aes_dec_block_256(&ciphertext, &key);
+/// assert_eq!(plaintext, std::array::from_fn(|i| i as u8 * 0x11));
+/// ```
+pub fn aes_dec_block_256(
+    ciphertext: &[u8; AES_BLOCK_SIZE],
+    key: &[u8; AES_256_KEY_SIZE],
+) -> [u8; AES_BLOCK_SIZE] {
+    let (expanded_key, nr) = fixed_key_schedule(key);
+    decrypt_block_expanded(ciphertext, &expanded_key, nr)
+}
+
+/// Fixed-size AES key types with their key schedule parameters.
+///
+/// This trait is implemented for `[u8; 16]`, `[u8; 24]` and `[u8; 32]` and
+/// carries the number of key words `NK` and rounds `NR` of the corresponding
+/// AES variant as constants. It is sealed, so it cannot be implemented for
+/// arrays of other lengths outside of this crate:
+///
+/// ```compile_fail
+/// use soft_aes::aes::AesKeyBytes;
+///
+/// struct Key20([u8; 20]);
+///
+/// impl AesKeyBytes for Key20 {
+///     const NK: usize = 5;
+///     const NR: usize = 11;
+///
+///     fn key_bytes(&self) -> &[u8] {
+///         &self.0
+///     }
+/// }
+/// ```
+pub trait AesKeyBytes: private::Sealed {
+    /// The number of 32-bit words in the key.
+    const NK: usize;
+    /// The number of rounds.
+    const NR: usize;
+
+    /// The key as a byte slice.
+    fn key_bytes(&self) -> &[u8];
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for [u8; super::AES_128_KEY_SIZE] {}
+    impl Sealed for [u8; super::AES_192_KEY_SIZE] {}
+    impl Sealed for [u8; super::AES_256_KEY_SIZE] {}
+}
+
+impl AesKeyBytes for [u8; AES_128_KEY_SIZE] {
+    const NK: usize = 4;
+    const NR: usize = 10;
+
+    fn key_bytes(&self) -> &[u8] {
+        self
     }
+}
 
-    // Final round (without inv_mix_columns)
-    inv_shift_rows(&mut state);
-    inv_sub_bytes(&mut state);
-    add_round_key(0, &mut state, &expanded_key);
+impl AesKeyBytes for [u8; AES_192_KEY_SIZE] {
+    const NK: usize = 6;
+    const NR: usize = 12;
+
+    fn key_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AesKeyBytes for [u8; AES_256_KEY_SIZE] {
+    const NK: usize = 8;
+    const NR: usize = 14;
+
+    fn key_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Encrypt a single block with a statically sized key of any AES variant.
+///
+/// The key size, and with it the number of rounds, is determined at compile
+/// time by the key type, so no validation is performed and the function
+/// cannot fail. The output is identical to `aes_enc_block` with the same key.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `key`: The key as a `[u8; 16]`, `[u8; 24]` or `[u8; 32]` array.
+///
+/// # Returns
+///
+/// The 16-byte ciphertext block.
+///
+/// # Example
+///
+/// ```
+/// use soft_aes::aes::{aes_enc_block, aes_enc_block_typed};
+///
+/// let key = [0x2bu8; 24];
+/// let block = [0u8; 16];
+///
+/// assert_eq!(
+///     aes_enc_block_typed(&block, &key),
+///     aes_enc_block(&block, &key).unwrap()
+/// );
+/// ```
+pub fn aes_enc_block_typed<K: AesKeyBytes>(
+    block: &[u8; AES_BLOCK_SIZE],
+    key: &K,
+) -> [u8; AES_BLOCK_SIZE] {
+    #[cfg(feature = "weak-key-check")]
+    super::aes_weak_key::warn_weak_key(key.key_bytes());
+
+    let expanded_key = expand_key(key.key_bytes(), K::NK, K::NR);
+    encrypt_block_expanded(block, &expanded_key, K::NR)
+}
+
+/// Expand a key whose length is known to be valid from its array type.
+fn fixed_key_schedule(key: &[u8]) -> ([u8; 240], usize) {
+    #[cfg(feature = "weak-key-check")]
+    super::aes_weak_key::warn_weak_key(key);
+
+    let (nk, nr) = calculate_parameters(key.len());
+
+    (expand_key(key, nk, nr), nr)
+}
+
+/// Encrypt a single block with a caller-provided S-box.
+///
+/// **Research use only. Non-standard and insecure.** This is AES with the
+/// S-box replaced by `sbox` in both SubBytes and the SubWord step of the key
+/// schedule. It does not compute AES unless the standard S-box is passed, and
+/// modified S-boxes can destroy the security of the cipher entirely. It is
+/// meant for cryptanalysis experiments and is only available with the
+/// `research` feature.
+///
+/// # Parameters
+///
+/// * `block`: The 16-byte plaintext block.
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+/// * `sbox`: The substitution table. It must be a permutation.
+/// * `inv_sbox`: The inverse of `sbox`. It is required so that a mismatched
+///   pair is rejected before any experiment relies on it.
+///
+/// # Returns
+///
+/// * `Ok([u8; AES_BLOCK_SIZE])` - The encrypted block.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid or `inv_sbox` is not
+///   the inverse of `sbox`.
+#[cfg(feature = "research")]
+pub fn aes_enc_block_with_sbox(
+    block: &[u8; AES_BLOCK_SIZE],
+    key: &[u8],
+    sbox: &[u8; 256],
+    inv_sbox: &[u8; 256],
+) -> Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>> {
+    let (expanded_key, nr) = key_schedule_with_sbox(key, sbox, inv_sbox)?;
+
+    let mut state = copy_block_to_state(block);
+    encrypt_state_with_sbox(&mut state, &expanded_key, nr, sbox);
+
+    Ok(copy_state_to_block(&state))
+}
+
+/// Decrypt a single block with a caller-provided S-box.
+///
+/// **Research use only. Non-standard and insecure.** This is the inverse of
+/// `aes_enc_block_with_sbox`. The key schedule uses `sbox` and the inverse
+/// cipher uses `inv_sbox`. It is only available with the `research` feature.
+///
+/// # Parameters
+///
+/// * `ciphertext`: The 16-byte encrypted block.
+/// * `key`: The cipher key, 16, 24, or 32 bytes long.
+/// * `sbox`: The substitution table. It must be a permutation.
+/// * `inv_sbox`: The inverse of `sbox`.
+///
+/// # Returns
+///
+/// * `Ok([u8; AES_BLOCK_SIZE])` - The decrypted block.
+/// * `Err(Box<dyn Error>)` - If the key length is invalid or `inv_sbox` is not
+///   the inverse of `sbox`.
+#[cfg(feature = "research")]
+pub fn aes_dec_block_with_sbox(
+    ciphertext: &[u8; AES_BLOCK_SIZE],
+    key: &[u8],
+    sbox: &[u8; 256],
+    inv_sbox: &[u8; 256],
+) -> Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>> {
+    let (expanded_key, nr) = key_schedule_with_sbox(key, sbox, inv_sbox)?;
+
+    let mut state = copy_block_to_state(ciphertext);
+    decrypt_state_with_sbox(&mut state, &expanded_key, nr, inv_sbox);
 
     Ok(copy_state_to_block(&state))
 }
+
+/// Validate the key and the S-box pair and expand the key with the S-box.
+#[cfg(feature = "research")]
+fn key_schedule_with_sbox(
+    key: &[u8],
+    sbox: &[u8; 256],
+    inv_sbox: &[u8; 256],
+) -> Result<([u8; 240], usize), Box<dyn Error>> {
+    validate_key_len(key.len())?;
+
+    if (0..=255u8).any(|x| inv_sbox[sbox[x as usize] as usize] != x) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "AES CORE ERROR: The inverse S-box is not the inverse of the S-box",
+        )));
+    }
+
+    let (nk, nr) = calculate_parameters(key.len());
+
+    Ok((expand_key_with_sbox(key, nk, nr, sbox), nr))
+}
+
+/// Return copies of the standard AES S-box and inverse S-box.
+///
+/// These are the starting point for experiments with
+/// `aes_enc_block_with_sbox`. Only available with the `research` feature.
+#[cfg(feature = "research")]
+pub fn standard_sboxes() -> ([u8; 256], [u8; 256]) {
+    (S_BOX, INV_S_BOX)
+}
@@ -0,0 +1,296 @@
+//! AES Counter (CTR) Helpers
+//!
+//! This module provides building blocks for counter-based modes of operation
+//! like CTR, GCM and CCM. These modes encrypt a sequence of counter blocks to
+//! produce a keystream, where each counter block is derived from the previous
+//! one by incrementing a counter field inside the block.
+//!
+//! Different protocols lay out the counter block differently: the counter
+//! field can span a few bytes or the full block and can be stored in big- or
+//! little-endian byte order. Centralizing the increment logic lets all
+//! counter-based modes share one tested implementation.
+//!
+//! # Features
+//!
+//! - `increment_counter`: Increments the counter field in the low `width`
+//!   bytes of a counter block, wrapping around within that width.
+//!
+//! - `aes_enc_ctr_windowed` and `aes_dec_ctr_windowed`: Encrypt or decrypt
+//!   data in CTR mode split into fixed-size windows, each with its own
+//!   counter derived from the window index.
+//!
+//! - `aes_ctr_window`: Encrypts or decrypts a single window in isolation,
+//!   giving random access at window granularity.
+//!
+//! # Windowed CTR
+//!
+//! Container formats that encrypt data in fixed-size windows (e.g. 64 KiB)
+//! need to decrypt any window without processing the ones before it. The
+//! windowed functions therefore restart the keystream for every window. The
+//! counter block for block `j` of window `i` is
+//!
+//! ```text
+//! base_nonce (8) || i (4, big-endian) || j (4, big-endian)
+//! ```
+//!
+//! so window `i` can be processed on its own given `base_nonce` and `i`. A
+//! base nonce must never be reused under the same key.
+//!
+//! # Example
+//!
+//! Increment a 32-bit big-endian counter as used by GCM:
+//!
+//! ```
+//! use crate::soft_aes::aes::{increment_counter, Endian};
+//!
+//! let mut block = [0u8; 16];
+//! block[15] = 0xFF;
+//!
+//! increment_counter(&mut block, 4, Endian::Big).expect("Increment failed");
+//!
+//! assert_eq!(block[14..], [0x01, 0x00]);
+//! ```
+
+use super::aes_core::{encrypt_block_expanded, key_schedule, AES_BLOCK_SIZE};
+
+use std::error::Error;
+
+/// Byte order of the counter field in a counter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// The most significant byte of the counter comes first.
+    Big,
+    /// The least significant byte of the counter comes first.
+    Little,
+}
+
+/// Increment the counter field of a counter block in place.
+///
+/// The counter field occupies the last `width` bytes of the block and is
+/// interpreted in the given byte order. Only these bytes are modified; the
+/// increment wraps around to zero within the field instead of carrying into
+/// the rest of the block (e.g. into a nonce prefix).
+///
+/// # Arguments
+///
+/// * `block` - The counter block to increment.
+/// * `width` - The width of the counter field in bytes, 1 to 16.
+/// * `endian` - The byte order of the counter field.
+///
+/// # Returns
+///
+/// * `Ok(())` if the counter was incremented.
+/// * `Err(Box<dyn Error>)` if the width is invalid.
+pub fn increment_counter(
+    block: &mut [u8; AES_BLOCK_SIZE],
+    width: usize,
+    endian: Endian,
+) -> Result<(), Box<dyn Error>> {
+    if width == 0 || width > AES_BLOCK_SIZE {
+        return Err(format!(
+            "AES CTR ERROR: Counter width must be 1 to 16 bytes, got {}",
+            width
+        )
+        .into());
+    }
+
+    let counter = &mut block[AES_BLOCK_SIZE - width..];
+
+    match endian {
+        Endian::Big => increment_bytes(counter.iter_mut().rev()),
+        Endian::Little => increment_bytes(counter.iter_mut()),
+    }
+
+    Ok(())
+}
+
+/// Length of the base nonce used by the windowed CTR functions in bytes.
+pub const CTR_WINDOW_NONCE_SIZE: usize = 8;
+
+/// Maximum length of one window in bytes, limited by the 32-bit block counter.
+const CTR_WINDOW_MAX_LEN: u64 = (AES_BLOCK_SIZE as u64) << 32;
+
+/// Encrypt data in CTR mode split into fixed-size windows.
+///
+/// The data is split into windows of `window_size` bytes; the last window may
+/// be shorter. Each window is encrypted independently with the counter layout
+/// described in the module documentation, so that any single window can later
+/// be decrypted with `aes_ctr_window`.
+///
+/// # Arguments
+///
+/// * `data` - The plaintext.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `base_nonce` - A unique 8-byte nonce for the whole data.
+/// * `window_size` - The window size in bytes, at least 1 and at most 2^36.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The ciphertext, as long as the plaintext.
+/// * `Err(Box<dyn Error>)` - If the key or the window size is invalid, or the
+///   data spans more than 2^32 windows.
+pub fn aes_enc_ctr_windowed(
+    data: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    base_nonce: &[u8; CTR_WINDOW_NONCE_SIZE],
+    window_size: usize,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = data.as_ref();
+
+    if window_size == 0 || window_size as u64 > CTR_WINDOW_MAX_LEN {
+        return Err(format!(
+            "AES CTR ERROR: Window size must be 1 to 2^36 bytes, got {}",
+            window_size
+        )
+        .into());
+    }
+
+    if data.len().div_ceil(window_size) as u64 > 1 << 32 {
+        return Err("AES CTR ERROR: Data spans more than 2^32 windows".into());
+    }
+
+    let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+    let mut output = Vec::with_capacity(data.len());
+    for (index, window) in data.chunks(window_size).enumerate() {
+        ctr_window_xor(
+            window,
+            &expanded_key,
+            nr,
+            base_nonce,
+            index as u32,
+            &mut output,
+        );
+    }
+
+    Ok(output)
+}
+
+/// Decrypt data encrypted with `aes_enc_ctr_windowed`.
+///
+/// CTR decryption is the same operation as encryption; this function is
+/// provided for symmetry.
+///
+/// # Arguments
+///
+/// * `data` - The ciphertext.
+/// * `key` - The key used for encryption.
+/// * `base_nonce` - The base nonce used for encryption.
+/// * `window_size` - The window size used for encryption.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The plaintext.
+/// * `Err(Box<dyn Error>)` - If the key or the window size is invalid, or the
+///   data spans more than 2^32 windows.
+pub fn aes_dec_ctr_windowed(
+    data: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    base_nonce: &[u8; CTR_WINDOW_NONCE_SIZE],
+    window_size: usize,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    aes_enc_ctr_windowed(data, key, base_nonce, window_size)
+}
+
+/// Encrypt or decrypt a single window of windowed CTR data.
+///
+/// The result equals the corresponding slice of `aes_enc_ctr_windowed` (or
+/// `aes_dec_ctr_windowed`) over the full data, i.e. of bytes
+/// `window_index * window_size ..` up to the window length.
+///
+/// # Arguments
+///
+/// * `window` - The plaintext or ciphertext of one window.
+/// * `key` - AES-128, AES-192 or AES-256 key.
+/// * `base_nonce` - The base nonce of the full data.
+/// * `window_index` - The zero-based index of the window.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The processed window, as long as the input.
+/// * `Err(Box<dyn Error>)` - If the key is invalid or the window is longer
+///   than 2^36 bytes.
+pub fn aes_ctr_window(
+    window: &[u8],
+    key: impl AsRef<[u8]>,
+    base_nonce: &[u8; CTR_WINDOW_NONCE_SIZE],
+    window_index: u32,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if window.len() as u64 > CTR_WINDOW_MAX_LEN {
+        return Err(format!(
+            "AES CTR ERROR: Window must be at most 2^36 bytes, got {}",
+            window.len()
+        )
+        .into());
+    }
+
+    let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+    let mut output = Vec::with_capacity(window.len());
+    ctr_window_xor(
+        window,
+        &expanded_key,
+        nr,
+        base_nonce,
+        window_index,
+        &mut output,
+    );
+
+    Ok(output)
+}
+
+/// XOR one window with its keystream and append the result to `output`.
+fn ctr_window_xor(
+    window: &[u8],
+    expanded_key: &[u8; 240],
+    nr: usize,
+    base_nonce: &[u8; CTR_WINDOW_NONCE_SIZE],
+    window_index: u32,
+    output: &mut Vec<u8>,
+) {
+    let mut counter = [0u8; AES_BLOCK_SIZE];
+    counter[..CTR_WINDOW_NONCE_SIZE].copy_from_slice(base_nonce);
+    counter[CTR_WINDOW_NONCE_SIZE..12].copy_from_slice(&window_index.to_be_bytes());
+
+    let start = output.len();
+    output.extend_from_slice(window);
+
+    for chunk in output[start..].chunks_mut(AES_BLOCK_SIZE) {
+        let keystream = encrypt_block_expanded(&counter, expanded_key, nr);
+        xor_keystream(chunk, &keystream);
+        increment_bytes(counter[12..].iter_mut().rev());
+    }
+}
+
+/// XOR a keystream into `data` in place.
+///
+/// Only the first `data.len()` bytes of `keystream` are used, so a final
+/// partial block can be processed with a full keystream block. This is the
+/// common step of keystream-based modes such as CTR.
+///
+/// # Panics
+///
+/// Panics if `keystream` is shorter than `data`.
+pub(crate) fn xor_keystream(data: &mut [u8], keystream: &[u8]) {
+    assert!(
+        keystream.len() >= data.len(),
+        "AES CTR ERROR: Keystream of {} bytes is shorter than the data of {} bytes",
+        keystream.len(),
+        data.len()
+    );
+
+    for (d, k) in data.iter_mut().zip(keystream.iter()) {
+        *d ^= *k;
+    }
+}
+
+/// Add one to a multi-byte integer given its bytes from least to most
+/// significant, wrapping around on overflow.
+fn increment_bytes<'a>(bytes: impl Iterator<Item = &'a mut u8>) {
+    for byte in bytes {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
@@ -19,6 +19,21 @@
 //!   It also supports the removal of 0x80 and PKCS#7 padding if it was applied
 //!   during encryption.
 //!
+//! - `aes_enc_ecb_traced`: Encrypts like `aes_enc_ecb` and also returns the
+//!   padded plaintext blocks, for teaching and debugging.
+//!
+//! - `aes_enc_blocks_in_place` and `aes_dec_blocks_in_place`: Encrypt or
+//!   decrypt contiguous blocks in place, without padding or allocation.
+//!
+//! - `aes_enc_blocks_with_offsets`: Encrypts blocks with a per-block offset
+//!   (XEX), the building block of tweakable modes such as OCB and XTS.
+//!
+//! - `encrypt_blocks_iter`: Lazily encrypts a stream of blocks in ECB mode as
+//!   an iterator adapter, expanding the key only once.
+//!
+//! - `blocks_equal_under_key`: Checks whether two blocks encrypt to the same
+//!   ECB ciphertext block, e.g. for deduplication experiments.
+//!
 //! The implementation assumes that the provided key is of a valid length for
 //! AES (128, 192, or 256 bits). The module integrates closely with the core
 //! AES functionalities and the PKCS#7 padding module to offer a seamless
@@ -64,34 +79,24 @@ use std::error::Error;
 ///
 /// # Parameters
 /// - `plaintext`: The data to encrypt. It should be a multiple of
-///                `AES_BLOCK_SIZE` unless padding is applied.
+///   `AES_BLOCK_SIZE` unless padding is applied.
 /// - `key`: The encryption key.
 /// - `padding`: Optional padding method. Supported values are `None` (default),
-///              `PKCS7`, and `0x80`.
+///   `PKCS7`, and `0x80`.
 ///
 /// # Returns
 /// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the encrypted data
 /// or an error.
 pub fn aes_enc_ecb(
-    plaintext: &[u8],
-    key: &[u8],
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
     padding: Option<&str>,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let block_size = AES_BLOCK_SIZE;
-    let mut data = plaintext.to_vec();
+    let plaintext = plaintext.as_ref();
+    let key = key.as_ref();
 
-    // Apply padding if necessary
-    match padding {
-        Some("PKCS7") => pkcs7_pad(&mut data, block_size)?,
-        Some("0x80") => pad_80(&mut data, block_size)?,
-        None if data.len() % block_size != 0 => {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "AES ENC ECB Error: Plaintext must be a multiple of AES_BLOCK_SIZE for 'None' padding",
-            )));
-        }
-        _ => {}
-    }
+    let block_size = AES_BLOCK_SIZE;
+    let data = ecb_pad(plaintext, padding)?;
 
     let mut ciphertext = Vec::with_capacity(data.len());
 
@@ -106,27 +111,143 @@ pub fn aes_enc_ecb(
     Ok(ciphertext)
 }
 
+/// Encrypt data using AES in ECB mode and trace the blocks that are encrypted.
+///
+/// This is a teaching and debugging variant of `aes_enc_ecb`. Besides the
+/// ciphertext, it returns the plaintext blocks actually fed to the block
+/// cipher, i.e. after padding. This shows how the padding changes the final
+/// block and that ECB encrypts each block independently: block `i` of the
+/// ciphertext is `aes_enc_block(blocks[i], key)`.
+///
+/// # Parameters
+/// - `plaintext`: The data to encrypt. It should be a multiple of
+///   `AES_BLOCK_SIZE` unless padding is applied.
+/// - `key`: The encryption key.
+/// - `padding`: Optional padding method. Supported values are `None` (default),
+///   `PKCS7`, and `0x80`.
+///
+/// # Returns
+/// Returns a `Result<(Vec<u8>, Vec<[u8; AES_BLOCK_SIZE]>), Box<dyn Error>>`
+/// containing the encrypted data and the padded plaintext blocks, or an error.
+#[allow(clippy::type_complexity)]
+pub fn aes_enc_ecb_traced(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    padding: Option<&str>,
+) -> Result<(Vec<u8>, Vec<[u8; AES_BLOCK_SIZE]>), Box<dyn Error>> {
+    let data = ecb_pad(plaintext.as_ref(), padding)?;
+    let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+    let blocks: Vec<[u8; AES_BLOCK_SIZE]> = data
+        .chunks_exact(AES_BLOCK_SIZE)
+        .map(|block| {
+            let mut block_array = [0u8; AES_BLOCK_SIZE];
+            block_array.copy_from_slice(block);
+            block_array
+        })
+        .collect();
+
+    let mut ciphertext = Vec::with_capacity(data.len());
+    for block in &blocks {
+        ciphertext.extend_from_slice(&encrypt_block_expanded(block, &expanded_key, nr));
+    }
+
+    Ok((ciphertext, blocks))
+}
+
+/// Copy and pad the plaintext for ECB encryption.
+fn ecb_pad(plaintext: &[u8], padding: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let block_size = AES_BLOCK_SIZE;
+    let mut data = Vec::new();
+
+    // Reject unknown padding methods, which would otherwise leave the data
+    // unpadded
+    if let Some(other) = padding {
+        if other != "PKCS7" && other != "0x80" {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES ENC ECB Error: Unsupported padding '{}', expected 'PKCS7' or '0x80'",
+                    other
+                ),
+            )));
+        }
+    }
+
+    // Reserve the padded length up front so that padding does not reallocate
+    if matches!(padding, Some("PKCS7") | Some("0x80")) {
+        let padded = padded_len(plaintext.len(), block_size).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES ENC ECB Error: Input too large, got {} bytes",
+                    plaintext.len()
+                ),
+            )
+        })?;
+        data.try_reserve_exact(padded)?;
+    }
+    data.extend_from_slice(plaintext);
+
+    // Apply padding if necessary
+    match padding {
+        Some("PKCS7") => pkcs7_pad(&mut data, block_size)?,
+        Some("0x80") => pad_80(&mut data, block_size)?,
+        _ => {}
+    }
+
+    // Every byte must be covered by a full block, or it would be dropped
+    if !data.len().is_multiple_of(block_size) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "AES ENC ECB Error: Plaintext must be a multiple of AES_BLOCK_SIZE for 'None' padding",
+        )));
+    }
+
+    Ok(data)
+}
+
 /// Decrypt data using AES in ECB mode with optional padding removal.
 ///
 /// # Parameters
 /// - `ciphertext`: The encrypted data to decrypt. It should be a multiple of
-///                 `AES_BLOCK_SIZE`.
+///   `AES_BLOCK_SIZE`.
 /// - `key`: The decryption key.
 /// - `padding`: Optional padding method used during encryption. Supported values
-///              are `None` (default), `PKCS7`, and `0x80`.
+///   are `None` (default), `PKCS7`, and `0x80`.
 ///
 /// # Returns
 /// Returns a `Result<Vec<u8>, Box<dyn Error>>` containing the decrypted data
 /// or an error.
 pub fn aes_dec_ecb(
-    ciphertext: &[u8],
-    key: &[u8],
+    ciphertext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
     padding: Option<&str>,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    if ciphertext.len() % AES_BLOCK_SIZE != 0 {
+    let ciphertext = ciphertext.as_ref();
+    let key = key.as_ref();
+
+    // Reject unknown padding methods, which would otherwise leave the padding
+    // in the plaintext
+    if let Some(other) = padding {
+        if other != "PKCS7" && other != "0x80" {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "AES DEC ECB Error: Unsupported padding '{}', expected 'PKCS7' or '0x80'",
+                    other
+                ),
+            )));
+        }
+    }
+
+    if !ciphertext.len().is_multiple_of(AES_BLOCK_SIZE) {
         return Err(Box::new(std::io::Error::new(
             std::io::ErrorKind::InvalidInput,
-            "AES DEC ECB Error: Ciphertext must be a multiple of AES_BLOCK_SIZE",
+            format!(
+                "AES DEC ECB Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                ciphertext.len()
+            ),
         )));
     }
 
@@ -149,3 +270,223 @@ pub fn aes_dec_ecb(
 
     Ok(plaintext)
 }
+
+/// Encrypt contiguous blocks in place as independent blocks (ECB).
+///
+/// This is the ECB core without padding or allocation. The key is expanded
+/// once and every 16-byte block of `data` is replaced by its encryption. It
+/// is a building block for higher modes and for callers that handle padding
+/// themselves.
+///
+/// # Parameters
+/// - `data`: The blocks to encrypt. The length must be a multiple of
+///   `AES_BLOCK_SIZE`.
+/// - `key`: The encryption key.
+///
+/// # Returns
+/// Returns `Ok(())` if all blocks were encrypted, or an error if the data
+/// length or the key is invalid. On error `data` is left unchanged.
+pub fn aes_enc_blocks_in_place(
+    data: &mut [u8],
+    key: impl AsRef<[u8]>,
+) -> Result<(), Box<dyn Error>> {
+    let (expanded_key, nr) = blocks_in_place_schedule(data, key.as_ref(), "ENC")?;
+
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        let mut block_array = [0u8; AES_BLOCK_SIZE];
+        block_array.copy_from_slice(block);
+        block.copy_from_slice(&encrypt_block_expanded(&block_array, &expanded_key, nr));
+    }
+
+    Ok(())
+}
+
+/// Decrypt contiguous blocks in place as independent blocks (ECB).
+///
+/// This is the counterpart of `aes_enc_blocks_in_place`. No padding is
+/// removed.
+///
+/// # Parameters
+/// - `data`: The blocks to decrypt. The length must be a multiple of
+///   `AES_BLOCK_SIZE`.
+/// - `key`: The decryption key.
+///
+/// # Returns
+/// Returns `Ok(())` if all blocks were decrypted, or an error if the data
+/// length or the key is invalid. On error `data` is left unchanged.
+pub fn aes_dec_blocks_in_place(
+    data: &mut [u8],
+    key: impl AsRef<[u8]>,
+) -> Result<(), Box<dyn Error>> {
+    let (expanded_key, nr) = blocks_in_place_schedule(data, key.as_ref(), "DEC")?;
+
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        let mut block_array = [0u8; AES_BLOCK_SIZE];
+        block_array.copy_from_slice(block);
+        block.copy_from_slice(&decrypt_block_expanded(&block_array, &expanded_key, nr));
+    }
+
+    Ok(())
+}
+
+/// Encrypt blocks in place with a per-block offset (XEX).
+///
+/// Each block is masked with its offset before and after encryption:
+///
+/// ```text
+/// C[i] = E(K, P[i] ^ offsets[i]) ^ offsets[i]
+/// ```
+///
+/// This is the XOR-encrypt-XOR pattern at the core of tweakable modes such as
+/// OCB and XTS. The function is only a building block: deriving the offsets
+/// from a nonce or tweak is left to the mode built on top of it. The key is
+/// expanded once for all blocks.
+///
+/// # Parameters
+/// - `blocks`: The blocks to encrypt.
+/// - `key`: The encryption key.
+/// - `offsets`: One offset per block.
+///
+/// # Returns
+/// Returns `Ok(())` if all blocks were encrypted, or an error if the number
+/// of offsets does not match the number of blocks or the key is invalid. On
+/// error `blocks` is left unchanged.
+pub fn aes_enc_blocks_with_offsets(
+    blocks: &mut [[u8; AES_BLOCK_SIZE]],
+    key: impl AsRef<[u8]>,
+    offsets: &[[u8; AES_BLOCK_SIZE]],
+) -> Result<(), Box<dyn Error>> {
+    if blocks.len() != offsets.len() {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES ENC XEX Error: Expected one offset per block, got {} blocks and {} offsets",
+                blocks.len(),
+                offsets.len()
+            ),
+        )));
+    }
+
+    let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+    for (block, offset) in blocks.iter_mut().zip(offsets) {
+        for (b, o) in block.iter_mut().zip(offset) {
+            *b ^= o;
+        }
+        *block = encrypt_block_expanded(block, &expanded_key, nr);
+        for (b, o) in block.iter_mut().zip(offset) {
+            *b ^= o;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check whether two blocks encrypt to the same ciphertext under a key.
+///
+/// ECB is deterministic: under a fixed key, a plaintext block always encrypts
+/// to the same ciphertext block, and since AES is a permutation, different
+/// plaintext blocks always encrypt to different ciphertext blocks. The result
+/// is therefore `true` exactly if `a == b`. This property allows encrypted
+/// storage to detect duplicate blocks by comparing ciphertexts only, without
+/// keeping the plaintext.
+///
+/// **Security:** The same property is what makes ECB leak information. Anyone
+/// who sees the ciphertexts learns which blocks are equal, and repeated
+/// patterns in the data remain visible. Deduplication on ECB ciphertext is
+/// only acceptable where revealing block equality is part of the threat
+/// model.
+///
+/// The key is expanded once for both blocks.
+///
+/// # Parameters
+/// - `a`: The first plaintext block.
+/// - `b`: The second plaintext block.
+/// - `key`: The encryption key.
+///
+/// # Returns
+/// Returns `Ok(true)` if both blocks encrypt to the same ciphertext block,
+/// `Ok(false)` otherwise, or an error if the key is invalid.
+pub fn blocks_equal_under_key(
+    a: &[u8; AES_BLOCK_SIZE],
+    b: &[u8; AES_BLOCK_SIZE],
+    key: impl AsRef<[u8]>,
+) -> Result<bool, Box<dyn Error>> {
+    let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+    Ok(
+        encrypt_block_expanded(a, &expanded_key, nr)
+            == encrypt_block_expanded(b, &expanded_key, nr),
+    )
+}
+
+/// Check the data length and expand the key for the in-place block functions.
+fn blocks_in_place_schedule(
+    data: &[u8],
+    key: &[u8],
+    direction: &str,
+) -> Result<([u8; 240], usize), Box<dyn Error>> {
+    if !data.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "AES {} ECB Error: Data must be a multiple of AES_BLOCK_SIZE, got {} bytes",
+                direction,
+                data.len()
+            ),
+        )));
+    }
+
+    key_schedule(key)
+}
+
+/// Lazily encrypt a stream of blocks using AES in ECB mode.
+///
+/// The key is expanded once when the iterator is created. Each block is only
+/// encrypted when it is pulled from the returned iterator, so the adapter can
+/// be chained with other iterators without collecting the data into a `Vec`.
+///
+/// # Parameters
+/// - `blocks`: An iterator over the 16-byte plaintext blocks.
+/// - `key`: The encryption key.
+///
+/// # Returns
+/// Returns an iterator yielding a `Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>>`
+/// for each encrypted block. If the key is invalid, the first item is an error
+/// and the iterator ends after it.
+///
+/// # Example
+///
+/// ```
+/// use crate::soft_aes::aes::{aes_enc_block, encrypt_blocks_iter};
+///
+/// let key = b"Very secret key.";
+/// let blocks = vec![[0u8; 16], [1u8; 16]];
+///
+/// let encrypted: Vec<[u8; 16]> = encrypt_blocks_iter(blocks.into_iter(), key)
+///     .collect::<Result<_, _>>()
+///     .expect("Encryption failed");
+///
+/// assert_eq!(encrypted[1], aes_enc_block(&[1u8; 16], key).unwrap());
+/// ```
+pub fn encrypt_blocks_iter<'a, I>(
+    blocks: I,
+    key: impl AsRef<[u8]>,
+) -> impl Iterator<Item = Result<[u8; AES_BLOCK_SIZE], Box<dyn Error>>> + 'a
+where
+    I: Iterator<Item = [u8; AES_BLOCK_SIZE]> + 'a,
+{
+    let mut schedule = Some(key_schedule(key.as_ref()));
+    let mut blocks = blocks;
+
+    std::iter::from_fn(move || match schedule.as_ref()? {
+        Ok((expanded_key, nr)) => blocks
+            .next()
+            .map(|block| Ok(encrypt_block_expanded(&block, expanded_key, *nr))),
+        // Report an invalid key once, then end the iteration
+        Err(_) => match schedule.take()? {
+            Err(e) => Some(Err(e)),
+            Ok(_) => None,
+        },
+    })
+}
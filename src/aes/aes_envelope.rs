@@ -0,0 +1,615 @@
+//! AES Authenticated Envelope (CBC + CMAC)
+//!
+//! This module provides an opinionated, self-contained authenticated
+//! encryption format built from the primitives of this crate. It combines AES
+//! in CBC mode with PKCS#7 padding for confidentiality and AES-CMAC for
+//! integrity in an encrypt-then-MAC construction.
+//!
+//! A single master key is used. Independent encryption and MAC keys of the
+//! same size as the master key are derived from it with `derive_enc_mac_keys`,
+//! so the same key material is never used for both purposes.
+//!
+//! # Format
+//!
+//! The sealed envelope has the layout
+//!
+//! ```text
+//! version (1) || iv (16) || ciphertext (16 * n) || tag (16)
+//! ```
+//!
+//! where `tag = AES-CMAC(mac_key, version || iv || ciphertext)`. The version
+//! byte allows the format to evolve; the current version is `0x01`.
+//!
+//! # Features
+//!
+//! - `seal`: Encrypts and authenticates a plaintext into an envelope with a
+//!   random IV taken from the operating system.
+//!
+//! - `seal_with_iv`: The same with a caller-provided IV, e.g. for
+//!   reproducible output or platforms without an operating system RNG.
+//!
+//! - `open`: Verifies and decrypts an envelope. The tag is checked in constant
+//!   time before any decryption takes place.
+//!
+//! The encryption and MAC keys derived by `seal` and `open` are held in a
+//! `zeroize::Zeroizing` buffer and wiped before the functions return. The
+//! same holds for the expanded master key used for the derivation and the
+//! expanded encryption and MAC keys, whose first round key is the key itself.
+//! This is best effort: copies that the compiler makes in registers or on the
+//! stack while moving values cannot be wiped from Rust.
+//!
+//! The opening functions only return plaintext after the tag has been
+//! verified. If decryption fails after verification, the partially decrypted
+//! buffer is wiped before the error is returned.
+//!
+//! - `seal_detailed` and `CbcCmac::seal_detailed`: Return the ciphertext, IV,
+//!   tag and associated data length as a structured `SealResult`.
+//!
+//! - `CbcCmac`: The same encrypt-then-MAC construction as an AEAD with
+//!   separate encryption and MAC keys, a nonce and associated data.
+//!
+//! - `cmac_then_cbc_encrypt` and `cmac_then_cbc_decrypt`: The MAC-then-encrypt
+//!   order required by some legacy protocols. Prefer the encrypt-then-MAC
+//!   functions above for new designs.
+//!
+//! # Example
+//!
+//! ```
+//! use crate::soft_aes::aes::{open, seal};
+//!
+//! let plaintext = b"Example plaintext.";
+//! let key = b"Very secret key.";
+//!
+//! let sealed = seal(plaintext, key).expect("Sealing failed");
+//! let opened = open(&sealed, key).expect("Opening failed");
+//!
+//! assert_eq!(opened, plaintext);
+//! ```
+//!
+//! # Disclaimer
+//!
+//! - `seal` takes the IV from the random number generator of the operating
+//!   system. With `seal_with_iv`, `seal_detailed` and `CbcCmac`, the IV must
+//!   be generated by the caller from a cryptographically secure source and
+//!   must be unique for every envelope sealed under the same key.
+
+use super::super::padding::{padded_len, pkcs7_pad, pkcs7_unpad};
+use super::aes_cbc::{
+    aes_dec_cbc_in_place_unpad, aes_enc_cbc, cbc_decrypt_generic, cbc_encrypt_generic,
+};
+use super::aes_cmac::{aes_cmac, ct_eq, Cmac};
+use super::aes_core::{
+    decrypt_block_expanded, encrypt_block_expanded, key_schedule, num_rounds, AES_BLOCK_SIZE,
+};
+use super::aes_kdf::kdf_enc_mac_keys;
+
+use std::error::Error;
+use zeroize::Zeroizing;
+
+/// Current version of the envelope format.
+pub const ENVELOPE_VERSION: u8 = 0x01;
+
+/// Length of the authentication tag in bytes.
+const TAG_LEN: usize = 16;
+
+/// The derived `(enc_key, mac_key)` pair, wiped when it is dropped.
+pub(crate) type EnvelopeSubkeys = Zeroizing<(Vec<u8>, Vec<u8>)>;
+
+/// Seal a plaintext into an authenticated envelope with a random IV.
+///
+/// The IV is taken from the random number generator of the operating system
+/// and stored in the envelope, so the caller does not have to manage it.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to protect.
+/// * `key` - AES-128, AES-192 or AES-256 master key.
+///
+/// # Returns
+///
+/// A `Result` containing the envelope
+/// `version || iv || ciphertext || tag`, or an error if the key is invalid
+/// or no random IV could be generated.
+pub fn seal(plaintext: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut iv = [0u8; AES_BLOCK_SIZE];
+    getrandom::getrandom(&mut iv)
+        .map_err(|e| format!("AES ENVELOPE ERROR: IV generation failed: {}", e))?;
+
+    seal_with_iv(plaintext, key, &iv)
+}
+
+/// Seal a plaintext into an authenticated envelope with the given IV.
+///
+/// This is `seal` with a caller-provided IV. The IV must be random and must
+/// never be reused under the same key; prefer `seal` unless the IV has to be
+/// controlled, e.g. for reproducible test output.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to protect.
+/// * `key` - AES-128, AES-192 or AES-256 master key.
+/// * `iv` - A random, unique initialization vector for CBC mode.
+///
+/// # Returns
+///
+/// A `Result` containing the envelope
+/// `version || iv || ciphertext || tag`, or an error.
+pub fn seal_with_iv(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let detailed = seal_detailed(plaintext, key, iv)?;
+
+    let mut sealed = Vec::with_capacity(1 + AES_BLOCK_SIZE + detailed.ciphertext.len() + TAG_LEN);
+    sealed.push(ENVELOPE_VERSION);
+    sealed.extend_from_slice(&detailed.iv_or_nonce);
+    sealed.extend_from_slice(&detailed.ciphertext);
+    sealed.extend_from_slice(&detailed.tag);
+
+    Ok(sealed)
+}
+
+/// The parts of a sealed message, as returned by the `_detailed` seal
+/// functions.
+///
+/// This is meant for callers that record metadata, e.g. in an audit log. It
+/// holds everything needed to reconstruct the matching `open` call, apart
+/// from the key and the associated data itself. With the `serde` feature,
+/// `SealResult` implements `Serialize` and `Deserialize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SealResult {
+    /// The CBC ciphertext including the PKCS#7 padding.
+    pub ciphertext: Vec<u8>,
+    /// The IV (envelope) or nonce (`CbcCmac`) used for sealing.
+    pub iv_or_nonce: [u8; AES_BLOCK_SIZE],
+    /// The authentication tag.
+    pub tag: [u8; TAG_LEN],
+    /// The length of the associated data in bytes; 0 for the envelope.
+    pub aad_len: usize,
+}
+
+/// Seal a plaintext like `seal`, returning its parts as a `SealResult`.
+///
+/// The envelope for `open` is
+/// `ENVELOPE_VERSION || iv_or_nonce || ciphertext || tag`.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to protect.
+/// * `key` - AES-128, AES-192 or AES-256 master key.
+/// * `iv` - A random, unique initialization vector for CBC mode.
+///
+/// # Returns
+///
+/// A `Result` containing the `SealResult`, or an error.
+pub fn seal_detailed(
+    plaintext: impl AsRef<[u8]>,
+    key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+) -> Result<SealResult, Box<dyn Error>> {
+    let subkeys = derive_envelope_subkeys(key.as_ref())?;
+
+    let (ciphertext, tag) = cbc_cmac_seal(
+        &subkeys.0,
+        &subkeys.1,
+        &[ENVELOPE_VERSION],
+        iv,
+        plaintext.as_ref(),
+    )?;
+
+    Ok(SealResult {
+        ciphertext,
+        iv_or_nonce: *iv,
+        tag,
+        aad_len: 0,
+    })
+}
+
+/// Verify and decrypt an envelope created with `seal`.
+///
+/// The envelope is parsed and its tag is verified in constant time before the
+/// ciphertext is decrypted. On error, no plaintext is returned and any
+/// decrypted bytes are wiped.
+///
+/// # Arguments
+///
+/// * `data` - The envelope `version || iv || ciphertext || tag`.
+/// * `key` - The master key used to seal the envelope.
+///
+/// # Returns
+///
+/// A `Result` containing the plaintext, or an error if the envelope is
+/// malformed, has an unknown version or fails authentication.
+pub fn open(data: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = data.as_ref();
+
+    if data.len() < 1 + AES_BLOCK_SIZE + AES_BLOCK_SIZE + TAG_LEN {
+        return Err("AES ENVELOPE ERROR: Envelope is too short".into());
+    }
+
+    if data[0] != ENVELOPE_VERSION {
+        return Err(format!("AES ENVELOPE ERROR: Unsupported version {:#04x}", data[0]).into());
+    }
+
+    let subkeys = derive_envelope_subkeys(key.as_ref())?;
+
+    let mut iv = [0u8; AES_BLOCK_SIZE];
+    iv.copy_from_slice(&data[1..1 + AES_BLOCK_SIZE]);
+    let ciphertext = &data[1 + AES_BLOCK_SIZE..data.len() - TAG_LEN];
+    let tag = &data[data.len() - TAG_LEN..];
+
+    cbc_cmac_open(&subkeys.0, &subkeys.1, &data[..1], &iv, ciphertext, tag)
+}
+
+/// Derive the envelope encryption and MAC keys `(enc_key, mac_key)` into a
+/// buffer that is wiped when it is dropped.
+///
+/// Both keys have the size of the master key, so an AES-256 master key yields
+/// AES-256 encryption and MAC keys. The derived keys are only needed for the
+/// duration of `seal` or `open`, so they are zeroized as soon as these return,
+/// on success and on error. The expanded master key is wiped before this
+/// function returns.
+pub(crate) fn derive_envelope_subkeys(key: &[u8]) -> Result<EnvelopeSubkeys, Box<dyn Error>> {
+    let prf = Zeroizing::new(Cmac::new(key)?);
+
+    Ok(Zeroizing::new(kdf_enc_mac_keys(&prf, key.len())))
+}
+
+/// AES-CBC with AES-CMAC as an authenticated encryption with associated data
+/// (AEAD) scheme.
+///
+/// `CbcCmac` packages the encrypt-then-MAC construction behind a `seal`/`open`
+/// interface with a nonce and associated data, like other AEAD modes. The
+/// nonce is used as the CBC IV. The tag is computed as
+///
+/// ```text
+/// tag = AES-CMAC(mac_key, len(aad) || aad || nonce || ciphertext)
+/// ```
+///
+/// where `len(aad)` is the length of the associated data in bytes as a 64-bit
+/// big-endian integer. The length prefix keeps the boundary between the
+/// associated data and the rest of the MAC input unambiguous.
+///
+/// # Security
+///
+/// - **Authenticated IV (`reject_unauthenticated_iv`):** The nonce is the CBC
+///   IV and is always part of the MAC input. In plain CBC, flipping a bit of
+///   the IV flips the same bit of the first plaintext block without being
+///   noticed. Here, `open` rejects any modified nonce before decrypting.
+/// - **Key storage:** The keys are held in `zeroize::Zeroizing` buffers and
+///   wiped when the instance is dropped. `CbcCmac` is not `Clone`, so it does
+///   not create copies of the keys that outlive it.
+///
+/// # Example
+///
+/// ```
+/// use crate::soft_aes::aes::CbcCmac;
+///
+/// let cipher = CbcCmac::new(b"Encryption key..", b"MAC key.........").unwrap();
+/// let nonce = b"Random Init Vec.";
+///
+/// let sealed = cipher.seal(nonce, b"header", b"Example plaintext.").unwrap();
+/// let opened = cipher.open(nonce, b"header", &sealed).unwrap();
+///
+/// assert_eq!(opened, b"Example plaintext.");
+/// ```
+pub struct CbcCmac {
+    enc_key: Zeroizing<Vec<u8>>,
+    mac_key: Zeroizing<Vec<u8>>,
+}
+
+impl CbcCmac {
+    /// Create a new `CbcCmac` instance from independent encryption and MAC keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `enc_key` - AES-128, AES-192 or AES-256 key used for CBC encryption.
+    /// * `mac_key` - AES-128, AES-192 or AES-256 key used for AES-CMAC.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new instance, or an error if a key length is
+    /// invalid.
+    pub fn new(
+        enc_key: impl AsRef<[u8]>,
+        mac_key: impl AsRef<[u8]>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let enc_key = enc_key.as_ref();
+        let mac_key = mac_key.as_ref();
+
+        num_rounds(enc_key)?;
+        num_rounds(mac_key)?;
+
+        Ok(Self {
+            enc_key: Zeroizing::new(enc_key.to_vec()),
+            mac_key: Zeroizing::new(mac_key.to_vec()),
+        })
+    }
+
+    /// Encrypt and authenticate a plaintext together with associated data.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - A random, unique 16-byte nonce used as the CBC IV.
+    /// * `aad` - Associated data that is authenticated but not encrypted.
+    /// * `plaintext` - The data to encrypt.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `ciphertext || tag`, or an error.
+    pub fn seal(
+        &self,
+        nonce: &[u8; AES_BLOCK_SIZE],
+        aad: impl AsRef<[u8]>,
+        plaintext: impl AsRef<[u8]>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let SealResult {
+            ciphertext: mut sealed,
+            tag,
+            ..
+        } = self.seal_detailed(nonce, aad, plaintext)?;
+        sealed.extend_from_slice(&tag);
+
+        Ok(sealed)
+    }
+
+    /// Seal a plaintext like `seal`, returning its parts as a `SealResult`.
+    ///
+    /// The data for `open` is `ciphertext || tag`, with `iv_or_nonce` as the
+    /// nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - A random, unique 16-byte nonce used as the CBC IV.
+    /// * `aad` - Associated data that is authenticated but not encrypted.
+    /// * `plaintext` - The data to encrypt.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SealResult`, or an error.
+    pub fn seal_detailed(
+        &self,
+        nonce: &[u8; AES_BLOCK_SIZE],
+        aad: impl AsRef<[u8]>,
+        plaintext: impl AsRef<[u8]>,
+    ) -> Result<SealResult, Box<dyn Error>> {
+        let aad = aad.as_ref();
+
+        let (ciphertext, tag) = cbc_cmac_seal(
+            &self.enc_key,
+            &self.mac_key,
+            &aad_mac_prefix(aad),
+            nonce,
+            plaintext.as_ref(),
+        )?;
+
+        Ok(SealResult {
+            ciphertext,
+            iv_or_nonce: *nonce,
+            tag,
+            aad_len: aad.len(),
+        })
+    }
+
+    /// Verify and decrypt data created with `seal`.
+    ///
+    /// The tag is verified in constant time before the ciphertext is
+    /// decrypted. On error, no plaintext is returned and any decrypted bytes
+    /// are wiped.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce used to seal the data.
+    /// * `aad` - The associated data used to seal the data. It is not part of
+    ///   the sealed data and is not returned, so the caller must supply the
+    ///   same bytes as for `seal`. Any other value fails authentication.
+    /// * `data` - The sealed data `ciphertext || tag`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the plaintext, or an error if the data is
+    /// malformed or fails authentication.
+    pub fn open(
+        &self,
+        nonce: &[u8; AES_BLOCK_SIZE],
+        aad: impl AsRef<[u8]>,
+        data: impl AsRef<[u8]>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = data.as_ref();
+
+        if data.len() < AES_BLOCK_SIZE + TAG_LEN {
+            return Err("AES CBC-CMAC ERROR: Sealed data is too short".into());
+        }
+
+        let (ciphertext, tag) = data.split_at(data.len() - TAG_LEN);
+
+        cbc_cmac_open(
+            &self.enc_key,
+            &self.mac_key,
+            &aad_mac_prefix(aad.as_ref()),
+            nonce,
+            ciphertext,
+            tag,
+        )
+    }
+}
+
+/// Encrypt a plaintext together with its AES-CMAC (MAC-then-encrypt).
+///
+/// The output is
+///
+/// ```text
+/// AES-CBC(enc_key, iv, PKCS#7(plaintext || AES-CMAC(mac_key, plaintext)))
+/// ```
+///
+/// This order is used by some legacy protocols and is provided for
+/// interoperability with them. Unlike encrypt-then-MAC as in `seal` and
+/// `CbcCmac`, the receiver has to decrypt and remove the padding before the
+/// tag can be checked. An attacker who can tell a padding failure from a tag
+/// failure, by error or by timing, can use the receiver as a padding oracle
+/// and recover plaintext (cf. POODLE and Lucky Thirteen against TLS). Prefer
+/// encrypt-then-MAC for new designs.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to protect.
+/// * `enc_key` - AES-128, AES-192 or AES-256 key used for CBC encryption.
+/// * `mac_key` - AES-128, AES-192 or AES-256 key used for AES-CMAC.
+/// * `iv` - A random, unique initialization vector for CBC mode.
+///
+/// # Returns
+///
+/// A `Result` containing the ciphertext, or an error if a key is invalid.
+pub fn cmac_then_cbc_encrypt(
+    plaintext: impl AsRef<[u8]>,
+    enc_key: impl AsRef<[u8]>,
+    mac_key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let plaintext = plaintext.as_ref();
+
+    let tag = aes_cmac(plaintext, mac_key)?;
+
+    let mut tagged = Zeroizing::new(Vec::with_capacity(plaintext.len() + TAG_LEN));
+    tagged.extend_from_slice(plaintext);
+    tagged.extend_from_slice(&tag);
+
+    aes_enc_cbc(&*tagged, enc_key, iv, Some("PKCS7"))
+}
+
+/// Decrypt data created with `cmac_then_cbc_encrypt` and verify its tag.
+///
+/// The ciphertext is decrypted, the padding is removed and the trailing tag
+/// is compared in constant time with the AES-CMAC of the recovered plaintext.
+/// Padding and tag failures return the same error, and the decrypted buffer
+/// is wiped on every error path. The timing of the two failures still
+/// differs, see `cmac_then_cbc_encrypt` for the resulting risk.
+///
+/// # Arguments
+///
+/// * `ciphertext` - The data returned by `cmac_then_cbc_encrypt`.
+/// * `enc_key` - The key used for CBC encryption.
+/// * `mac_key` - The key used for AES-CMAC.
+/// * `iv` - The initialization vector used for encryption.
+///
+/// # Returns
+///
+/// A `Result` containing the plaintext, or an error if the ciphertext is
+/// malformed or fails verification.
+pub fn cmac_then_cbc_decrypt(
+    ciphertext: impl AsRef<[u8]>,
+    enc_key: impl AsRef<[u8]>,
+    mac_key: impl AsRef<[u8]>,
+    iv: &[u8; AES_BLOCK_SIZE],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let ciphertext = ciphertext.as_ref();
+    let enc_key = enc_key.as_ref();
+    let mac_key = mac_key.as_ref();
+
+    num_rounds(enc_key)?;
+    num_rounds(mac_key)?;
+
+    let mut tagged = Zeroizing::new(ciphertext.to_vec());
+    let verified = aes_dec_cbc_in_place_unpad(&mut tagged, enc_key, iv, Some("PKCS7")).is_ok()
+        && tagged.len() >= TAG_LEN
+        && {
+            let (plaintext, tag) = tagged.split_at(tagged.len() - TAG_LEN);
+            ct_eq(&aes_cmac(plaintext, mac_key)?, tag)
+        };
+
+    if !verified {
+        return Err("AES MAC-THEN-ENCRYPT ERROR: Decryption failed".into());
+    }
+
+    let plaintext_len = tagged.len() - TAG_LEN;
+    tagged.truncate(plaintext_len);
+
+    Ok(std::mem::take(&mut *tagged))
+}
+
+/// Build the MAC prefix `len(aad) || aad` used by `CbcCmac`.
+fn aad_mac_prefix(aad: &[u8]) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(8 + aad.len());
+    prefix.extend_from_slice(&(aad.len() as u64).to_be_bytes());
+    prefix.extend_from_slice(aad);
+    prefix
+}
+
+/// Encrypt-then-MAC: CBC-encrypt `plaintext` with PKCS#7 padding and compute
+/// the CMAC over `mac_prefix || iv || ciphertext`.
+///
+/// The expanded encryption and MAC keys are wiped before returning. Returns
+/// the ciphertext and the tag.
+pub(crate) fn cbc_cmac_seal(
+    enc_key: &[u8],
+    mac_key: &[u8],
+    mac_prefix: &[u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, [u8; TAG_LEN]), Box<dyn Error>> {
+    let schedule = Zeroizing::new(key_schedule(enc_key)?);
+
+    // Reserve the padded length up front, so that padding does not leave a
+    // copy of the plaintext behind in a reallocated buffer
+    let padded = padded_len(plaintext.len(), AES_BLOCK_SIZE)
+        .ok_or("AES ENVELOPE ERROR: Plaintext is too large")?;
+    let mut ciphertext = Vec::with_capacity(padded);
+    ciphertext.extend_from_slice(plaintext);
+    pkcs7_pad(&mut ciphertext, AES_BLOCK_SIZE)?;
+    cbc_encrypt_generic(&mut ciphertext, iv, |block| {
+        encrypt_block_expanded(block, &schedule.0, schedule.1)
+    })?;
+
+    let tag = cbc_cmac_tag(mac_key, mac_prefix, iv, &ciphertext)?;
+
+    Ok((ciphertext, tag))
+}
+
+/// Verify the CMAC over `mac_prefix || iv || ciphertext` in constant time and
+/// only then CBC-decrypt the ciphertext and remove the PKCS#7 padding.
+///
+/// The IV is covered by the tag, so a modified IV is rejected like a modified
+/// ciphertext. The plaintext is decrypted in a buffer that is wiped when it is
+/// dropped, so it only leaves this function through the `Ok` arm, after
+/// successful verification. The expanded keys are wiped as well.
+pub(crate) fn cbc_cmac_open(
+    enc_key: &[u8],
+    mac_key: &[u8],
+    mac_prefix: &[u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let expected_tag = cbc_cmac_tag(mac_key, mac_prefix, iv, ciphertext)?;
+
+    if !ct_eq(&expected_tag, tag) {
+        return Err("AES ENVELOPE ERROR: Authentication failed".into());
+    }
+
+    let schedule = Zeroizing::new(key_schedule(enc_key)?);
+    let mut plaintext = Zeroizing::new(ciphertext.to_vec());
+    cbc_decrypt_generic(&mut plaintext, iv, |block| {
+        decrypt_block_expanded(block, &schedule.0, schedule.1)
+    })?;
+    pkcs7_unpad(&mut plaintext)?;
+
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+/// Compute the CMAC over `mac_prefix || iv || ciphertext`, wiping the
+/// expanded MAC key afterwards.
+fn cbc_cmac_tag(
+    mac_key: &[u8],
+    mac_prefix: &[u8],
+    iv: &[u8; AES_BLOCK_SIZE],
+    ciphertext: &[u8],
+) -> Result<[u8; TAG_LEN], Box<dyn Error>> {
+    let mut mac_input = Vec::with_capacity(mac_prefix.len() + AES_BLOCK_SIZE + ciphertext.len());
+    mac_input.extend_from_slice(mac_prefix);
+    mac_input.extend_from_slice(iv);
+    mac_input.extend_from_slice(ciphertext);
+
+    Ok(Zeroizing::new(Cmac::new(mac_key)?).mac(&mac_input))
+}
@@ -0,0 +1,148 @@
+//! AES-CMAC Based Key Derivation
+//!
+//! This module provides simple key derivation functions built on top of
+//! AES-CMAC. CMAC is a pseudorandom function (PRF) when keyed with a secret
+//! key, so computing it over distinct, fixed labels yields independent keys
+//! from a single master key.
+//!
+//! # Features
+//!
+//! - `derive_subkeys`: Derives a 128-bit encryption key and a 128-bit MAC key
+//!   from one master key using domain-separated labels.
+//!
+//! - `derive_enc_mac_keys`: Derives an encryption key and a MAC key of any
+//!   AES key size with the NIST SP 800-108 KDF in counter mode, using AES-CMAC
+//!   as the PRF.
+//!
+//! # Example
+//!
+//! ```
+//! use crate::soft_aes::aes::derive_subkeys;
+//!
+//! let master = b"Very secret key.";
+//! let (enc_key, mac_key) = derive_subkeys(master).expect("Derivation failed");
+//!
+//! assert_ne!(enc_key, mac_key);
+//! ```
+//!
+//! # References
+//!
+//! - NIST SP 800-108r1: Recommendation for Key Derivation Using Pseudorandom
+//!   Functions, Section 4.1 (KDF in Counter Mode)
+//!
+//! # Disclaimer
+//!
+//! - The master key must be a uniformly random AES key. These functions do
+//!   not stretch low-entropy secrets such as passwords.
+
+use super::aes_cmac::{aes_cmac, Cmac};
+
+use std::error::Error;
+
+/// Label used to derive the encryption key in `derive_subkeys` and
+/// `derive_enc_mac_keys`.
+const LABEL_ENC: &[u8] = b"enc";
+
+/// Label used to derive the MAC key in `derive_subkeys` and
+/// `derive_enc_mac_keys`.
+const LABEL_MAC: &[u8] = b"mac";
+
+/// Derive an encryption key and a MAC key from a single master key.
+///
+/// The keys are computed as `enc_key = AES-CMAC(master, "enc")` and
+/// `mac_key = AES-CMAC(master, "mac")`. The distinct labels provide domain
+/// separation, so the two keys are independent of each other. The derivation
+/// is deterministic for a given master key.
+///
+/// # Arguments
+///
+/// * `master` - AES-128, AES-192 or AES-256 master key.
+///
+/// # Returns
+///
+/// A `Result` containing the tuple `(enc_key, mac_key)` of 128-bit keys, or
+/// an error if the master key length is invalid.
+pub fn derive_subkeys(master: impl AsRef<[u8]>) -> Result<([u8; 16], [u8; 16]), Box<dyn Error>> {
+    let master = master.as_ref();
+
+    let enc_key = aes_cmac(LABEL_ENC, master)?;
+    let mac_key = aes_cmac(LABEL_MAC, master)?;
+
+    Ok((enc_key, mac_key))
+}
+
+/// Derive an encryption key and a MAC key of the given size from a single
+/// master key.
+///
+/// Each key is derived with the KDF in counter mode of NIST SP 800-108,
+/// using AES-CMAC keyed with the master key as the PRF. Block `i` (starting
+/// at 1) of the key derived for `label` is
+///
+/// ```text
+/// AES-CMAC(master, [i]_32 || label || 0x00 || [key_size * 8]_32)
+/// ```
+///
+/// where `[x]_32` is the 32-bit big-endian encoding of `x` and no context is
+/// used. The blocks are concatenated and truncated to `key_size` bytes. The
+/// encryption key uses the ASCII label `"enc"` and the MAC key the ASCII
+/// label `"mac"`, so the two keys are independent of each other. Another
+/// implementation using the same labels and encoding derives the same keys.
+///
+/// The derived keys can be passed to two-key constructions such as
+/// `CbcCmac`.
+///
+/// # Arguments
+///
+/// * `master` - AES-128, AES-192 or AES-256 master key.
+/// * `key_size` - Size of each derived key in bytes: 16, 24 or 32.
+///
+/// # Returns
+///
+/// A `Result` containing the tuple `(enc_key, mac_key)`, or an error if the
+/// master key length or the requested key size is invalid.
+pub fn derive_enc_mac_keys(
+    master: impl AsRef<[u8]>,
+    key_size: usize,
+) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
+    let master = master.as_ref();
+
+    if !matches!(key_size, 16 | 24 | 32) {
+        return Err(format!(
+            "AES KDF ERROR: Invalid key size. Expected 16, 24, or 32 bytes, got {} bytes",
+            key_size
+        )
+        .into());
+    }
+
+    Ok(kdf_enc_mac_keys(&Cmac::new(master)?, key_size))
+}
+
+/// `derive_enc_mac_keys` with the PRF already keyed with the master key.
+///
+/// The caller owns the expanded master key and can wipe it afterwards. The
+/// key size must be 16, 24 or 32.
+pub(crate) fn kdf_enc_mac_keys(prf: &Cmac, key_size: usize) -> (Vec<u8>, Vec<u8>) {
+    (
+        kdf_counter(prf, LABEL_ENC, key_size),
+        kdf_counter(prf, LABEL_MAC, key_size),
+    )
+}
+
+/// NIST SP 800-108 KDF in counter mode with AES-CMAC as the PRF.
+fn kdf_counter(prf: &Cmac, label: &[u8], key_size: usize) -> Vec<u8> {
+    let length_bits = (key_size as u32 * 8).to_be_bytes();
+    let mut derived = Vec::with_capacity(key_size.next_multiple_of(16));
+
+    for counter in 1..=key_size.div_ceil(16) as u32 {
+        let mut input = Vec::with_capacity(4 + label.len() + 1 + 4);
+        input.extend_from_slice(&counter.to_be_bytes());
+        input.extend_from_slice(label);
+        input.push(0x00);
+        input.extend_from_slice(&length_bits);
+
+        derived.extend_from_slice(&prf.mac(&input));
+    }
+    derived.truncate(key_size);
+
+    derived
+}
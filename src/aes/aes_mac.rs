@@ -0,0 +1,263 @@
+//! AES CBC-MAC and PMAC
+//!
+//! This module provides two further block cipher MACs besides AES-CMAC. Both
+//! implement the `Mac` trait of the CMAC module, so verification code written
+//! against `Mac` works with any of `Cmac`, `CbcMac` and `Pmac`.
+//!
+//! # Features
+//!
+//! - `CbcMac`: CBC-MAC as specified in ISO/IEC 9797-1, MAC algorithm 1 with
+//!   padding method 2 (`0x80` followed by zeros, always applied).
+//!
+//! - `Pmac`: PMAC1 by Rogaway, a parallelizable MAC. The blocks are masked with
+//!   independent offsets, so they can be processed in any order.
+//!
+//! # Example
+//!
+//! ```
+//! use crate::soft_aes::aes::{Cmac, Mac, Pmac};
+//!
+//! fn check(mac: &impl Mac, message: &[u8], tag: &[u8]) -> bool {
+//!     mac.verify(message, tag)
+//! }
+//!
+//! let key = b"Very secret key.";
+//! let pmac = Pmac::new(key).unwrap();
+//! let tag = pmac.mac(b"message");
+//!
+//! assert!(check(&pmac, b"message", &tag));
+//! assert!(!check(&Cmac::new(key).unwrap(), b"message", &tag));
+//! ```
+//!
+//! # References
+//!
+//! - ISO/IEC 9797-1:2011, Message Authentication Codes (MACs) - Part 1:
+//!   Mechanisms using a block cipher
+//! - P. Rogaway, "Efficient Instantiations of Tweakable Blockciphers and
+//!   Refinements to Modes OCB and PMAC", ASIACRYPT 2004
+//!   [https://web.cs.ucdavis.edu/~rogaway/ocb/pmac.htm]
+//!
+//! # Disclaimer
+//!
+//! - CBC-MAC is only secure for messages of one fixed length under a given
+//!   key. For variable-length messages an attacker can forge tags from known
+//!   ones. Use AES-CMAC unless a protocol requires CBC-MAC.
+//! - PMAC was covered by patents which have been abandoned; check the status
+//!   for your jurisdiction before use.
+
+use super::aes_cmac::Mac;
+use super::aes_core::{encrypt_block_expanded, key_schedule, AES_BLOCK_SIZE};
+
+use std::error::Error;
+
+/// AES CBC-MAC (ISO/IEC 9797-1 MAC algorithm 1, padding method 2).
+///
+/// The message is padded with `0x80` and zeros to a multiple of the block
+/// size, even if it is already aligned, and encrypted in CBC mode with a zero
+/// IV. The tag is the last ciphertext block.
+///
+/// **Only use CBC-MAC for messages of one fixed length.** See the module
+/// disclaimer.
+#[derive(Clone)]
+pub struct CbcMac {
+    expanded_key: [u8; 240],
+    nr: usize,
+}
+
+impl CbcMac {
+    /// Expand the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - AES-128, AES-192 or AES-256 key.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new instance, or an error if the key length
+    /// is invalid.
+    pub fn new(key: impl AsRef<[u8]>) -> Result<Self, Box<dyn Error>> {
+        let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+        Ok(Self { expanded_key, nr })
+    }
+
+    /// Compute the CBC-MAC of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message for which to compute the MAC.
+    ///
+    /// # Returns
+    ///
+    /// The 128-bit MAC.
+    pub fn mac(&self, message: impl AsRef<[u8]>) -> [u8; 16] {
+        let message = message.as_ref();
+
+        // All complete blocks except a final one that is followed by padding
+        let full_len = message.len() - message.len() % AES_BLOCK_SIZE;
+        let mut x = [0u8; AES_BLOCK_SIZE];
+        for chunk in message[..full_len].chunks_exact(AES_BLOCK_SIZE) {
+            xor_in_place(&mut x, chunk);
+            x = encrypt_block_expanded(&x, &self.expanded_key, self.nr);
+        }
+
+        // Padding method 2 always adds a block holding 0 to 15 message bytes
+        let rest = &message[full_len..];
+        let mut last = [0u8; AES_BLOCK_SIZE];
+        last[..rest.len()].copy_from_slice(rest);
+        last[rest.len()] = 0x80;
+
+        xor_in_place(&mut x, &last);
+        encrypt_block_expanded(&x, &self.expanded_key, self.nr)
+    }
+}
+
+impl Mac for CbcMac {
+    fn mac(&self, message: &[u8]) -> [u8; 16] {
+        CbcMac::mac(self, message)
+    }
+}
+
+impl std::fmt::Debug for CbcMac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CbcMac")
+            .field("expanded_key", &"<redacted>")
+            .field("nr", &self.nr)
+            .finish()
+    }
+}
+
+/// AES-PMAC (PMAC1).
+///
+/// With `L = AES(K, 0^128)`, block `i` of `m` blocks is masked with the
+/// offset `Z_i = Z_{i-1} XOR L(ntz(i))`, where `L(j)` is `L` doubled `j`
+/// times in GF(2^128) and `ntz(i)` the number of trailing zero bits of `i`.
+/// For `i < m`, `AES(K, M_i XOR Z_i)` is added to the checksum. The last
+/// block is added directly, XORed with `L * x^-1` if it is complete or
+/// padded with `0x80` and zeros otherwise. The tag is the encryption of the
+/// checksum.
+#[derive(Clone)]
+pub struct Pmac {
+    expanded_key: [u8; 240],
+    nr: usize,
+    l: [[u8; 16]; 64],
+    l_inv: [u8; 16],
+}
+
+impl Pmac {
+    /// Expand the key and precompute the offsets `L(j)` and `L * x^-1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - AES-128, AES-192 or AES-256 key.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new instance, or an error if the key length
+    /// is invalid.
+    pub fn new(key: impl AsRef<[u8]>) -> Result<Self, Box<dyn Error>> {
+        let (expanded_key, nr) = key_schedule(key.as_ref())?;
+
+        // L(0) := AES(K, 0^128), L(j) := dbl(L(j - 1))
+        let mut l = [[0u8; 16]; 64];
+        l[0] = encrypt_block_expanded(&[0u8; 16], &expanded_key, nr);
+        for j in 1..l.len() {
+            l[j] = dbl(&l[j - 1]);
+        }
+        let l_inv = inv_dbl(&l[0]);
+
+        Ok(Self {
+            expanded_key,
+            nr,
+            l,
+            l_inv,
+        })
+    }
+
+    /// Compute the PMAC of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message for which to compute the MAC.
+    ///
+    /// # Returns
+    ///
+    /// The 128-bit MAC.
+    pub fn mac(&self, message: impl AsRef<[u8]>) -> [u8; 16] {
+        let message = message.as_ref();
+
+        // As in CMAC, an empty message is one incomplete block
+        let n = message.len().div_ceil(AES_BLOCK_SIZE).max(1);
+        let last_block_start = AES_BLOCK_SIZE * (n - 1);
+
+        let mut offset = [0u8; 16];
+        let mut checksum = [0u8; 16];
+        for (i, chunk) in message[..last_block_start]
+            .chunks_exact(AES_BLOCK_SIZE)
+            .enumerate()
+        {
+            // ntz(i + 1) < 64 for any block index
+            xor_in_place(&mut offset, &self.l[(i + 1).trailing_zeros() as usize]);
+
+            let mut block = offset;
+            xor_in_place(&mut block, chunk);
+            let encrypted = encrypt_block_expanded(&block, &self.expanded_key, self.nr);
+            xor_in_place(&mut checksum, &encrypted);
+        }
+
+        let last = &message[last_block_start..];
+        if last.len() == AES_BLOCK_SIZE {
+            xor_in_place(&mut checksum, last);
+            xor_in_place(&mut checksum, &self.l_inv);
+        } else {
+            let mut padded = [0u8; 16];
+            padded[..last.len()].copy_from_slice(last);
+            padded[last.len()] = 0x80;
+            xor_in_place(&mut checksum, &padded);
+        }
+
+        encrypt_block_expanded(&checksum, &self.expanded_key, self.nr)
+    }
+}
+
+impl Mac for Pmac {
+    fn mac(&self, message: &[u8]) -> [u8; 16] {
+        Pmac::mac(self, message)
+    }
+}
+
+impl std::fmt::Debug for Pmac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pmac")
+            .field("expanded_key", &"<redacted>")
+            .field("nr", &self.nr)
+            .field("l", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Multiply a block by `x` in GF(2^128), i.e. double it.
+fn dbl(block: &[u8; 16]) -> [u8; 16] {
+    let value = u128::from_be_bytes(*block);
+    let reduction = if value >> 127 == 1 { 0x87 } else { 0 };
+
+    ((value << 1) ^ reduction).to_be_bytes()
+}
+
+/// Multiply a block by `x^-1` in GF(2^128), the inverse of `dbl`.
+fn inv_dbl(block: &[u8; 16]) -> [u8; 16] {
+    let value = u128::from_be_bytes(*block);
+
+    if value & 1 == 1 {
+        (((value ^ 0x87) >> 1) | 1 << 127).to_be_bytes()
+    } else {
+        (value >> 1).to_be_bytes()
+    }
+}
+
+/// XOR `data` into `block`.
+fn xor_in_place(block: &mut [u8; 16], data: &[u8]) {
+    for (b, d) in block.iter_mut().zip(data.iter()) {
+        *b ^= *d;
+    }
+}
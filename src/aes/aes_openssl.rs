@@ -0,0 +1,227 @@
+//! OpenSSL `Salted__` Format Compatibility
+//!
+//! This module reads and writes files in the format of the legacy password
+//! based encryption of the OpenSSL command line tool, e.g.
+//!
+//! ```text
+//! openssl enc -aes-256-cbc -md md5 -pass pass:password -in plain.txt -out cipher.bin
+//! ```
+//!
+//! Such files start with the magic `Salted__` followed by an 8-byte salt. The
+//! key and IV are derived from the password and the salt with the OpenSSL
+//! `EVP_BytesToKey` function using MD5 and a single iteration. The remaining
+//! data is the AES-CBC ciphertext with PKCS#7 padding.
+//!
+//! # Format
+//!
+//! ```text
+//! "Salted__" (8) || salt (8) || ciphertext (16 * n)
+//! ```
+//!
+//! # Features
+//!
+//! - `decrypt_openssl`: Parses the salt header, derives the key and IV and
+//!   decrypts the ciphertext.
+//!
+//! - `encrypt_openssl`: Encrypts with a random salt into a file that
+//!   `openssl enc -d -md md5` decrypts with the same password.
+//!
+//! - `encrypt_openssl_with_salt`: The same with a caller-provided salt, for
+//!   reproducible output.
+//!
+//! # Example
+//!
+//! ```
+//! use crate::soft_aes::aes::decrypt_openssl;
+//!
+//! // printf 'Hello' | openssl enc -aes-128-cbc -md md5 -pass pass:password
+//! let data = [
+//!     0x53, 0x61, 0x6c, 0x74, 0x65, 0x64, 0x5f, 0x5f, 0xe0, 0x69, 0x07, 0x0c, 0xe9, 0xc4,
+//!     0x2b, 0x8f, 0x1f, 0x73, 0x5a, 0x5f, 0x41, 0x28, 0x4d, 0x20, 0xf5, 0xb5, 0xb4, 0xf7,
+//!     0x2e, 0x61, 0xe0, 0x95,
+//! ];
+//!
+//! let plaintext = decrypt_openssl(&data, b"password", "aes-128-cbc").expect("Decryption failed");
+//! assert_eq!(plaintext, b"Hello");
+//! ```
+//!
+//! # References
+//!
+//! - OpenSSL documentation: `openssl-enc(1)` and `EVP_BytesToKey(3)`
+//!
+//! # Disclaimer
+//!
+//! - The `EVP_BytesToKey` derivation with MD5 and a single iteration is fast
+//!   to brute force and is only provided to read existing files. Since
+//!   OpenSSL 1.1.0 the command line tool uses SHA-256 by default; files must
+//!   be created with `-md md5` to be readable by this module.
+//! - The format is not authenticated. A wrong password is only detected by
+//!   invalid padding, and a modified file may decrypt to garbage. New data
+//!   should use an authenticated format such as the envelope of this crate.
+
+use super::aes_cbc::{aes_dec_cbc, aes_enc_cbc};
+use super::aes_core::AES_BLOCK_SIZE;
+
+use md5::{Digest, Md5};
+use std::error::Error;
+
+/// Magic prefix of the OpenSSL salted format.
+pub const OPENSSL_SALT_MAGIC: &[u8; 8] = b"Salted__";
+
+/// Size of the salt following the magic prefix.
+pub const OPENSSL_SALT_SIZE: usize = 8;
+
+/// Size of the header made of the magic prefix and the salt.
+const HEADER_SIZE: usize = OPENSSL_SALT_MAGIC.len() + OPENSSL_SALT_SIZE;
+
+/// Encrypt data into the OpenSSL `Salted__` format with a random salt.
+///
+/// The salt is taken from the random number generator of the operating
+/// system. The output is byte-compatible with
+/// `openssl enc -<cipher> -md md5` and can be decrypted with
+///
+/// ```text
+/// openssl enc -d -aes-256-cbc -md md5 -pass pass:password -in cipher.bin
+/// ```
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to encrypt.
+/// * `password` - The password to derive the key and IV from.
+/// * `cipher` - The OpenSSL cipher name: `aes-128-cbc`, `aes-192-cbc` or
+///   `aes-256-cbc`.
+///
+/// # Returns
+///
+/// A `Result` containing `Salted__ || salt || ciphertext`, or an error if the
+/// cipher name is unsupported or no random salt could be generated.
+pub fn encrypt_openssl(
+    plaintext: impl AsRef<[u8]>,
+    password: impl AsRef<[u8]>,
+    cipher: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; OPENSSL_SALT_SIZE];
+    getrandom::getrandom(&mut salt)
+        .map_err(|e| format!("OPENSSL COMPAT ERROR: Salt generation failed: {}", e))?;
+
+    encrypt_openssl_with_salt(plaintext, password, cipher, &salt)
+}
+
+/// Encrypt data into the OpenSSL `Salted__` format with the given salt.
+///
+/// This is `encrypt_openssl` with a caller-provided salt, equivalent to
+/// `openssl enc -<cipher> -md md5 -S <salt>` in OpenSSL versions that write
+/// the header for an explicit salt. The salt must be random and unique for
+/// every file encrypted under the same password; it is mainly meant for
+/// reproducible test output.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to encrypt.
+/// * `password` - The password to derive the key and IV from.
+/// * `cipher` - The OpenSSL cipher name: `aes-128-cbc`, `aes-192-cbc` or
+///   `aes-256-cbc`.
+/// * `salt` - The 8-byte salt written to the header.
+///
+/// # Returns
+///
+/// A `Result` containing `Salted__ || salt || ciphertext`, or an error if the
+/// cipher name is unsupported.
+pub fn encrypt_openssl_with_salt(
+    plaintext: impl AsRef<[u8]>,
+    password: impl AsRef<[u8]>,
+    cipher: &str,
+    salt: &[u8; OPENSSL_SALT_SIZE],
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let key_len = openssl_key_len(cipher)?;
+    let (key, iv) = evp_bytes_to_key(password.as_ref(), salt, key_len);
+
+    let ciphertext = aes_enc_cbc(plaintext, &key, &iv, Some("PKCS7"))?;
+
+    let mut data = Vec::with_capacity(HEADER_SIZE + ciphertext.len());
+    data.extend_from_slice(OPENSSL_SALT_MAGIC);
+    data.extend_from_slice(salt);
+    data.extend_from_slice(&ciphertext);
+
+    Ok(data)
+}
+
+/// Decrypt data in the OpenSSL `Salted__` format.
+///
+/// The key and IV are derived from the password and the salt of the header
+/// with `EVP_BytesToKey` (MD5, one iteration), and the ciphertext is
+/// decrypted in CBC mode with PKCS#7 padding removed.
+///
+/// # Arguments
+///
+/// * `data` - The file contents, starting with `Salted__` and the salt.
+/// * `password` - The password the file was encrypted with.
+/// * `cipher` - The OpenSSL cipher name: `aes-128-cbc`, `aes-192-cbc` or
+///   `aes-256-cbc`.
+///
+/// # Returns
+///
+/// A `Result` containing the plaintext, or an error if the cipher name is
+/// unsupported, the header is missing, or decryption fails, e.g. because of
+/// a wrong password.
+pub fn decrypt_openssl(
+    data: impl AsRef<[u8]>,
+    password: impl AsRef<[u8]>,
+    cipher: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = data.as_ref();
+    let key_len = openssl_key_len(cipher)?;
+
+    if data.len() < HEADER_SIZE || !data.starts_with(OPENSSL_SALT_MAGIC) {
+        return Err("OPENSSL COMPAT ERROR: Missing Salted__ header".into());
+    }
+
+    let salt = &data[OPENSSL_SALT_MAGIC.len()..HEADER_SIZE];
+    let (key, iv) = evp_bytes_to_key(password.as_ref(), salt, key_len);
+
+    aes_dec_cbc(&data[HEADER_SIZE..], &key, &iv, Some("PKCS7"))
+}
+
+/// Key length in bytes of a supported OpenSSL cipher name.
+fn openssl_key_len(cipher: &str) -> Result<usize, Box<dyn Error>> {
+    match cipher.to_ascii_lowercase().as_str() {
+        "aes-128-cbc" => Ok(16),
+        "aes-192-cbc" => Ok(24),
+        "aes-256-cbc" => Ok(32),
+        _ => Err(format!("OPENSSL COMPAT ERROR: Unsupported cipher '{}'", cipher).into()),
+    }
+}
+
+/// OpenSSL `EVP_BytesToKey` with MD5 and a single iteration.
+///
+/// The digests `D_1 = MD5(password || salt)` and
+/// `D_i = MD5(D_{i-1} || password || salt)` are concatenated until
+/// `key_len + AES_BLOCK_SIZE` bytes are available. The first `key_len` bytes
+/// form the key and the following `AES_BLOCK_SIZE` bytes the IV.
+fn evp_bytes_to_key(
+    password: &[u8],
+    salt: &[u8],
+    key_len: usize,
+) -> (Vec<u8>, [u8; AES_BLOCK_SIZE]) {
+    let mut material = Vec::with_capacity(key_len + AES_BLOCK_SIZE + 16);
+    let mut previous: Option<[u8; 16]> = None;
+
+    while material.len() < key_len + AES_BLOCK_SIZE {
+        let mut hasher = Md5::new();
+        if let Some(digest) = previous {
+            hasher.update(digest);
+        }
+        hasher.update(password);
+        hasher.update(salt);
+
+        let digest: [u8; 16] = hasher.finalize().into();
+        material.extend_from_slice(&digest);
+        previous = Some(digest);
+    }
+
+    let mut iv = [0u8; AES_BLOCK_SIZE];
+    iv.copy_from_slice(&material[key_len..key_len + AES_BLOCK_SIZE]);
+    material.truncate(key_len);
+
+    (material, iv)
+}
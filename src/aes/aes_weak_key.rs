@@ -0,0 +1,128 @@
+//! Weak Key Detection
+//!
+//! This module detects AES keys that are very likely accidental test
+//! artifacts, such as all-zero keys or the well-known keys from the FIPS-197
+//! and NIST SP 800-38A examples. Such keys are fine in unit tests but are a
+//! serious operational mistake when they leak into production.
+//!
+//! The module is only compiled with the `weak-key-check` feature. Without the
+//! feature, neither the check nor the warning exists in the binary.
+//!
+//! # Features
+//!
+//! - `weak_key_warning`: Returns a description of why a key looks like a test
+//!   key, or `None` for keys that pass the check.
+//!
+//! - In debug builds, every key passed in by the caller is checked and a
+//!   warning is printed to standard error the first time a weak key is used.
+//!   Keys fixed by an algorithm, such as the all-zero key of the CTR_DRBG
+//!   instantiation, are not checked.
+//!   The check never returns an error and does not change any results.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "weak-key-check")]
+//! # {
+//! use crate::soft_aes::aes::weak_key_warning;
+//!
+//! assert!(weak_key_warning(&[0u8; 16]).is_some());
+//! assert!(weak_key_warning(b"Ks8#vQ2!mZ7pL0xR").is_none());
+//! # }
+//! ```
+//!
+//! # Disclaimer
+//!
+//! - Passing this check does not mean a key is strong. Keys must be generated
+//!   from a cryptographically secure random source.
+
+#[cfg(test)]
+use std::cell::Cell;
+use std::sync::Once;
+
+/// Well-known example keys from FIPS-197 and NIST SP 800-38A.
+const TEST_KEYS: [&[u8]; 6] = [
+    // FIPS-197, Appendix C
+    &[
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ],
+    &[
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+    ],
+    &[
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f,
+    ],
+    // FIPS-197, Appendix A and NIST SP 800-38A, Appendix F
+    &[
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ],
+    &[
+        0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52, 0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90, 0x79,
+        0xe5, 0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b,
+    ],
+    &[
+        0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77,
+        0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14,
+        0xdf, 0xf4,
+    ],
+];
+
+/// Ensures the debug warning is printed only once per process.
+static WARN_ONCE: Once = Once::new();
+
+#[cfg(test)]
+thread_local! {
+    /// Number of weak keys detected on the current thread, for tests.
+    pub(crate) static WEAK_KEYS_DETECTED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Check whether a key looks like an accidental test key.
+///
+/// A key is reported if all of its bytes are identical (e.g. all `0x00` or
+/// all `0xFF`) or if it matches one of the well-known example keys from
+/// FIPS-197 and NIST SP 800-38A.
+///
+/// # Arguments
+///
+/// * `key` - The key to check.
+///
+/// # Returns
+///
+/// `Some` with a description of the problem if the key looks weak, `None`
+/// otherwise.
+pub fn weak_key_warning(key: &[u8]) -> Option<&'static str> {
+    if let Some((first, rest)) = key.split_first() {
+        if rest.iter().all(|b| b == first) {
+            return Some("key consists of a single repeated byte");
+        }
+    }
+
+    if TEST_KEYS.contains(&key) {
+        return Some("key matches a well-known test vector key");
+    }
+
+    None
+}
+
+/// Print a warning to standard error the first time a weak key is used.
+///
+/// This only has an effect in debug builds. Keys that are fixed by an
+/// algorithm, such as the all-zero CTR_DRBG key, are not passed here, so the
+/// single warning is kept for keys provided by the caller.
+pub(crate) fn warn_weak_key(key: &[u8]) {
+    if !cfg!(any(debug_assertions, test)) {
+        return;
+    }
+
+    if let Some(reason) = weak_key_warning(key) {
+        #[cfg(test)]
+        WEAK_KEYS_DETECTED.with(|count| count.set(count.get() + 1));
+
+        WARN_ONCE.call_once(|| eprintln!("AES WARNING: Weak key detected: {}", reason));
+    }
+}
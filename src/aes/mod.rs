@@ -1,12 +1,54 @@
+#[cfg(feature = "cbc")]
 mod aes_cbc;
+#[cfg(feature = "ccm")]
+mod aes_ccm;
+#[cfg(feature = "cmac")]
 mod aes_cmac;
+#[cfg(feature = "cmac-prf")]
+mod aes_cmac_prf;
+#[cfg(feature = "config")]
+mod aes_config;
 mod aes_core;
+#[cfg(feature = "ctr")]
+mod aes_ctr;
+#[cfg(feature = "ecb")]
 mod aes_ecb;
+#[cfg(feature = "envelope")]
+mod aes_envelope;
+#[cfg(feature = "kdf")]
+mod aes_kdf;
+#[cfg(feature = "mac")]
+mod aes_mac;
+#[cfg(feature = "openssl-compat")]
+mod aes_openssl;
+#[cfg(feature = "weak-key-check")]
+mod aes_weak_key;
 
+#[cfg(feature = "cbc")]
 pub use aes_cbc::*;
+#[cfg(feature = "ccm")]
+pub use aes_ccm::*;
+#[cfg(feature = "cmac")]
 pub use aes_cmac::*;
+#[cfg(feature = "cmac-prf")]
+pub use aes_cmac_prf::*;
+#[cfg(feature = "config")]
+pub use aes_config::*;
 pub use aes_core::*;
+#[cfg(feature = "ctr")]
+pub use aes_ctr::*;
+#[cfg(feature = "ecb")]
 pub use aes_ecb::*;
+#[cfg(feature = "envelope")]
+pub use aes_envelope::*;
+#[cfg(feature = "kdf")]
+pub use aes_kdf::*;
+#[cfg(feature = "mac")]
+pub use aes_mac::*;
+#[cfg(feature = "openssl-compat")]
+pub use aes_openssl::*;
+#[cfg(feature = "weak-key-check")]
+pub use aes_weak_key::*;
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,32 @@
+#[cfg(any(feature = "cbc", feature = "cmac"))]
+mod alloc_count;
+#[cfg(feature = "cbc")]
 mod test_aes_cbc;
+#[cfg(feature = "ccm")]
+mod test_aes_ccm;
+#[cfg(feature = "cmac")]
 mod test_aes_cmac;
+#[cfg(feature = "cmac-prf")]
+mod test_aes_cmac_prf;
+#[cfg(feature = "config")]
+mod test_aes_config;
 mod test_aes_core;
+#[cfg(feature = "cross-validate")]
+mod test_aes_cross_validate;
+#[cfg(feature = "ctr")]
+mod test_aes_ctr;
+#[cfg(feature = "ecb")]
 mod test_aes_ecb;
+#[cfg(feature = "envelope")]
+mod test_aes_envelope;
+#[cfg(feature = "kdf")]
+mod test_aes_kdf;
+#[cfg(feature = "mac")]
+mod test_aes_mac;
+#[cfg(feature = "openssl-compat")]
+mod test_aes_openssl;
+#[cfg(feature = "weak-key-check")]
+mod test_aes_weak_key;
 
-#[cfg(test)]
+#[cfg(feature = "ecb")]
 mod nist;
@@ -58,7 +58,7 @@ fn test_aes_enc_ecb_vartxt_kat_aes_128() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(&plaintext, key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -89,7 +89,7 @@ fn test_aes_enc_ecb_vartxt_kat_aes_192() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(&plaintext, key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -120,7 +120,7 @@ fn test_aes_enc_ecb_vartxt_kat_aes_256() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(&plaintext, key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -150,7 +150,7 @@ fn test_aes_dec_ecb_vartxt_kat_aes_128() {
         let expected_plaintext =
             hex::decode(expected_plaintext_hex).expect("Failed to decode plaintext hex");
 
-        let plaintext = aes_dec_ecb(&ciphertext, &key, None).expect("Decryption failed");
+        let plaintext = aes_dec_ecb(&ciphertext, key, None).expect("Decryption failed");
 
         assert_eq!(
             plaintext, expected_plaintext,
@@ -180,7 +180,7 @@ fn test_aes_dec_ecb_vartxt_kat_aes_192() {
         let expected_plaintext =
             hex::decode(expected_plaintext_hex).expect("Failed to decode plaintext hex");
 
-        let plaintext = aes_dec_ecb(&ciphertext, &key, None).expect("Decryption failed");
+        let plaintext = aes_dec_ecb(&ciphertext, key, None).expect("Decryption failed");
 
         assert_eq!(
             plaintext, expected_plaintext,
@@ -210,7 +210,7 @@ fn test_aes_dec_ecb_vartxt_kat_aes_256() {
         let expected_plaintext =
             hex::decode(expected_plaintext_hex).expect("Failed to decode plaintext hex");
 
-        let plaintext = aes_dec_ecb(&ciphertext, &key, None).expect("Decryption failed");
+        let plaintext = aes_dec_ecb(&ciphertext, key, None).expect("Decryption failed");
 
         assert_eq!(
             plaintext, expected_plaintext,
@@ -241,7 +241,7 @@ fn test_aes_enc_ecb_varkey_kat_aes_128() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(plaintext, &key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -272,7 +272,7 @@ fn test_aes_enc_ecb_varkey_kat_aes_192() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(plaintext, &key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -303,7 +303,7 @@ fn test_aes_enc_ecb_varkey_kat_aes_256() {
         let expected_ciphertext =
             hex::decode(expected_ciphertext_hex).expect("Failed to decode ciphertext hex");
 
-        let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+        let ciphertext = aes_enc_ecb(plaintext, &key, None).expect("Encryption failed");
 
         assert_eq!(
             ciphertext, expected_ciphertext,
@@ -1,4 +1,6 @@
 use super::super::aes_cbc::*;
+use super::super::aes_core::{aes_dec_block, aes_enc_block, Block16};
+use super::alloc_count::count_allocations;
 
 use hex;
 
@@ -23,7 +25,7 @@ fn test_aes_enc_cbc_no_padding() {
     ];
 
     // Call the AES CBC encryption function
-    let ciphertext = aes_enc_cbc(&plaintext, &key, &iv, None).expect("Encryption failed");
+    let ciphertext = aes_enc_cbc(plaintext, key, &iv, None).expect("Encryption failed");
 
     // Assert that the produced ciphertext matches the expected ciphertext
     assert_eq!(
@@ -55,7 +57,7 @@ fn test_aes_enc_cbc_with_pkcs7_padding() {
     ];
 
     // Call the AES CBC encryption function with PKCS#7 padding
-    let ciphertext = aes_enc_cbc(&plaintext, &key, &iv, Some("PKCS7")).expect("Encryption failed");
+    let ciphertext = aes_enc_cbc(plaintext, key, &iv, Some("PKCS7")).expect("Encryption failed");
 
     // Assert that the produced ciphertext matches the expected ciphertext
     assert_eq!(
@@ -85,7 +87,7 @@ fn test_aes_dec_cbc_no_padding() {
     ];
 
     // Call the AES CBC decryption function
-    let plaintext = aes_dec_cbc(&ciphertext, &key, &iv, None).expect("Decryption failed");
+    let plaintext = aes_dec_cbc(ciphertext, key, &iv, None).expect("Decryption failed");
 
     // Assert that the produced plaintext matches the expected plaintext
     assert_eq!(
@@ -117,7 +119,7 @@ fn test_aes_dec_cbc_with_pkcs7_padding() {
     ];
 
     // Call the AES CBC decryption function with PKCS#7 padding removal
-    let plaintext = aes_dec_cbc(&ciphertext, &key, &iv, Some("PKCS7")).expect("Decryption failed");
+    let plaintext = aes_dec_cbc(&ciphertext, key, &iv, Some("PKCS7")).expect("Decryption failed");
 
     // Convert the expected plaintext to Vec<u8> for comparison
     let expected_plaintext_vec: Vec<u8> = expected_plaintext.to_vec();
@@ -135,7 +137,7 @@ fn test_aes_enc_cbc_error_invalid_plaintext_length() {
     let key = [0u8; 16];
     let iv = [0u8; 16];
 
-    let result = aes_enc_cbc(&plaintext, &key, &iv, None);
+    let result = aes_enc_cbc(plaintext, key, &iv, None);
 
     assert!(result.is_err());
     if let Err(e) = result {
@@ -152,13 +154,13 @@ fn test_aes_dec_cbc_error_invalid_ciphertext_length() {
     let key = [0u8; 16];
     let iv = [0u8; 16];
 
-    let result = aes_dec_cbc(&ciphertext, &key, &iv, None);
+    let result = aes_dec_cbc(ciphertext, key, &iv, None);
 
     assert!(result.is_err());
     if let Err(e) = result {
         assert_eq!(
             e.to_string(),
-            "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE"
+            "AES DEC CBC Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got 10 bytes"
         );
     }
 }
@@ -170,7 +172,7 @@ fn test_aes_enc_cbc_with_80_padding() {
     let iv = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
     let expected_ciphertext = hex::decode("8C9D8A1544C87C97ED44C81382B7FBA7").unwrap();
 
-    let iv_array: [u8; 16] = iv.try_into().expect("Invalid IV length");
+    let iv_array: [u8; 16] = Block16::try_from(iv).expect("Invalid IV length").into();
 
     let ciphertext =
         aes_enc_cbc(&plaintext, &key, &iv_array, Some("0x80")).expect("Encryption failed");
@@ -188,7 +190,7 @@ fn test_aes_dec_cbc_with_80_padding_removal() {
     let iv = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
     let expected_plaintext = hex::decode("FFFFFFFFFFFFFFFF").unwrap();
 
-    let iv_array: [u8; 16] = iv.try_into().expect("Invalid IV length");
+    let iv_array: [u8; 16] = Block16::try_from(iv).expect("Invalid IV length").into();
 
     let plaintext =
         aes_dec_cbc(&ciphertext, &key, &iv_array, Some("0x80")).expect("Decryption failed");
@@ -201,3 +203,449 @@ fn test_aes_dec_cbc_with_80_padding_removal() {
         "Decrypted plaintext does not match expected value with 0x80 padding removal"
     );
 }
+
+#[test]
+fn test_aes_enc_cbc_reuse_matches_aes_enc_cbc() {
+    let plaintext = hex::decode("0102030405060708090A0B0C0D0E0F1011121314").unwrap();
+    let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+    let iv: [u8; 16] = hex::decode("FE5BF04A231CA7795AEE7EC2E43B144A")
+        .unwrap()
+        .try_into()
+        .expect("Invalid IV length");
+
+    let expected = aes_enc_cbc(&plaintext, &key, &iv, Some("PKCS7")).expect("Encryption failed");
+
+    let mut scratch = Vec::new();
+    aes_enc_cbc_reuse(&plaintext, &key, &iv, Some("PKCS7"), &mut scratch)
+        .expect("Encryption failed");
+
+    assert_eq!(scratch, expected);
+}
+
+#[test]
+fn test_aes_enc_cbc_reuse_discards_stale_data() {
+    let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+    let iv = [0x42u8; 16];
+    let long_plaintext = [0xAAu8; 64];
+    let short_plaintext = [0x55u8; 5];
+
+    let mut scratch = Vec::new();
+    aes_enc_cbc_reuse(long_plaintext, &key, &iv, Some("PKCS7"), &mut scratch)
+        .expect("Encryption failed");
+    assert_eq!(scratch.len(), 80);
+
+    aes_enc_cbc_reuse(short_plaintext, &key, &iv, Some("PKCS7"), &mut scratch)
+        .expect("Encryption failed");
+
+    let expected =
+        aes_enc_cbc(short_plaintext, &key, &iv, Some("PKCS7")).expect("Encryption failed");
+    assert_eq!(scratch, expected);
+    assert_eq!(scratch.len(), 16);
+}
+
+#[test]
+fn test_aes_enc_cbc_reuse_error_clears_scratch() {
+    let key = [0u8; 16];
+    let iv = [0u8; 16];
+    let mut scratch = vec![0xFFu8; 32];
+
+    let result = aes_enc_cbc_reuse([0u8; 10], key, &iv, None, &mut scratch);
+
+    assert!(result.is_err());
+    assert!(scratch.is_empty());
+}
+
+#[test]
+fn test_aes_enc_cbc_reuse_rejects_unknown_padding() {
+    let key = [0u8; 16];
+    let iv = [0u8; 16];
+
+    for padding in ["pkcs7", "pkcs#7", "PKCS#7", "0X80", ""] {
+        let mut scratch = vec![0xFFu8; 32];
+        let result = aes_enc_cbc_reuse(
+            b"0123456789abcdefSECRET!",
+            key,
+            &iv,
+            Some(padding),
+            &mut scratch,
+        );
+
+        assert!(result.is_err(), "padding {:?}", padding);
+        assert!(scratch.is_empty());
+    }
+}
+
+#[test]
+fn test_aes_enc_cbc_reuse_rejects_unaligned_plaintext() {
+    let key = [0u8; 16];
+    let iv = [0u8; 16];
+    let mut scratch = Vec::new();
+
+    let result = aes_enc_cbc_reuse(b"0123456789abcdefSECRET!", key, &iv, None, &mut scratch);
+
+    assert!(result.is_err());
+    assert!(scratch.is_empty());
+}
+
+#[test]
+fn test_aes_dec_cbc_in_place_unpad_round_trip() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    for padding in [Some("PKCS7"), Some("0x80")] {
+        for len in [0, 1, 15, 16, 17, 32, 45] {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+            let mut buf = aes_enc_cbc(&plaintext, key, iv, padding).unwrap();
+            let ciphertext_len = buf.len();
+
+            let plaintext_len = aes_dec_cbc_in_place_unpad(&mut buf, key, iv, padding).unwrap();
+
+            assert_eq!(plaintext_len, len);
+            assert_eq!(buf, plaintext);
+            assert!(buf.capacity() >= ciphertext_len);
+        }
+    }
+}
+
+#[test]
+fn test_aes_dec_cbc_in_place_unpad_matches_aes_dec_cbc() {
+    // NIST SP 800-38A, F.2.2 CBC-AES128.Decrypt
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let iv: [u8; 16] = hex::decode("000102030405060708090a0b0c0d0e0f")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let ciphertext = hex::decode(
+        "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+         73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7",
+    )
+    .unwrap();
+
+    let mut buf = ciphertext.clone();
+    let len = aes_dec_cbc_in_place_unpad(&mut buf, &key, &iv, None).unwrap();
+
+    assert_eq!(len, 64);
+    assert_eq!(buf, aes_dec_cbc(&ciphertext, &key, &iv, None).unwrap());
+}
+
+#[test]
+fn test_aes_dec_cbc_in_place_unpad_invalid_length() {
+    let mut buf = vec![0u8; 17];
+    let result = aes_dec_cbc_in_place_unpad(&mut buf, b"Very secret key.", &[0u8; 16], None);
+
+    assert!(result.is_err());
+    assert_eq!(buf, vec![0u8; 17]);
+}
+
+#[test]
+fn test_aes_dec_cbc_in_place_unpad_invalid_padding_clears_buffer() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    // An all-zero plaintext block is not valid PKCS#7 padding
+    let mut buf = aes_enc_cbc([0u8; 16], key, iv, None).unwrap();
+    let result = aes_dec_cbc_in_place_unpad(&mut buf, key, iv, Some("PKCS7"));
+
+    assert!(result.is_err());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_aes_enc_cbc_chained_matches_single_stream() {
+    // NIST SP 800-38A, F.2.1 CBC-AES128.Encrypt, split into three records
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let iv: [u8; 16] = hex::decode("000102030405060708090a0b0c0d0e0f")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let plaintext = hex::decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+    let expected = aes_enc_cbc(&plaintext, &key, &iv, None).unwrap();
+
+    let mut chained = Vec::new();
+    let mut next_iv = iv;
+    for record in [&plaintext[..16], &plaintext[16..48], &plaintext[48..]] {
+        let (ciphertext, last_block) = aes_enc_cbc_chained(record, &key, &next_iv, None).unwrap();
+        assert_eq!(last_block[..], ciphertext[ciphertext.len() - 16..]);
+
+        chained.extend_from_slice(&ciphertext);
+        next_iv = last_block;
+    }
+
+    assert_eq!(chained, expected);
+}
+
+#[test]
+fn test_aes_enc_cbc_chained_empty_record_keeps_iv() {
+    let iv = b"Random Init Vec.";
+    let (ciphertext, next_iv) = aes_enc_cbc_chained([], b"Very secret key.", iv, None).unwrap();
+
+    assert!(ciphertext.is_empty());
+    assert_eq!(&next_iv, iv);
+}
+
+#[test]
+fn test_aes_enc_cbc_output_is_exactly_sized() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    for len in [0, 1, 15, 16, 17, 100] {
+        let plaintext = vec![0x42u8; len];
+        for padding in [Some("PKCS7"), Some("0x80")] {
+            let ciphertext = aes_enc_cbc(&plaintext, key, iv, padding).unwrap();
+            assert_eq!(ciphertext.len(), (len / 16 + 1) * 16);
+            assert_eq!(ciphertext.capacity(), ciphertext.len());
+        }
+    }
+}
+
+#[test]
+fn test_aes_enc_cbc_allocates_once() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    for len in [0, 1, 15, 16, 17, 100] {
+        let plaintext = vec![0x42u8; len];
+        for padding in [None, Some("PKCS7"), Some("0x80")] {
+            if padding.is_none() && !len.is_multiple_of(16) {
+                continue;
+            }
+
+            let (ciphertext, allocations) =
+                count_allocations(|| aes_enc_cbc(&plaintext, key, iv, padding).unwrap());
+
+            // Only the output buffer is allocated, with its final size
+            let expected = usize::from(!ciphertext.is_empty());
+            assert_eq!(allocations, expected, "length {}, {:?}", len, padding);
+        }
+    }
+}
+
+#[test]
+fn test_aes_enc_cbc_rejects_unknown_padding() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    // Without padding, the trailing "SECRET!" must not reach the output in clear
+    for padding in ["pkcs7", "pkcs#7", "0X80"] {
+        assert!(aes_enc_cbc(b"0123456789abcdefSECRET!", key, iv, Some(padding)).is_err());
+    }
+    assert!(aes_enc_cbc(b"0123456789abcdef", key, iv, Some("pkcs7")).is_err());
+}
+
+#[test]
+fn test_aes_dec_cbc_rejects_unknown_padding() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+    let ciphertext = aes_enc_cbc(b"Example plaintext.", key, iv, Some("PKCS7")).unwrap();
+
+    // The padding must not be returned as part of the plaintext
+    for padding in ["pkcs7", "pkcs#7", "0X80"] {
+        assert!(aes_dec_cbc(&ciphertext, key, iv, Some(padding)).is_err());
+
+        let mut buf = ciphertext.clone();
+        assert!(aes_dec_cbc_in_place_unpad(&mut buf, key, iv, Some(padding)).is_err());
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn test_aes_enc_cbc_large_input() {
+    let plaintext = vec![0x42u8; 256 * 1024];
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+
+    let ciphertext = aes_enc_cbc(&plaintext, key, iv, Some("PKCS7")).expect("Encryption failed");
+    assert_eq!(ciphertext.len(), plaintext.len() + 16);
+    assert_eq!(
+        aes_dec_cbc(&ciphertext, key, iv, Some("PKCS7")).expect("Decryption failed"),
+        plaintext
+    );
+}
+
+/// Fill a buffer with deterministic pseudo-random bytes (xorshift64).
+fn fill_pseudo_random(buf: &mut [u8], state: &mut u64) {
+    for byte in buf {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *byte = *state as u8;
+    }
+}
+
+#[test]
+fn test_aes_enc_cbc_matches_manual_chaining() {
+    use super::super::aes_core::aes_enc_block;
+    use crate::padding::pkcs7_pad;
+
+    let mut state = 0x2545_f491_4f6c_dd1d;
+
+    for key_len in [16, 24, 32] {
+        for len in [16, 48, 80, 1, 31, 77] {
+            let mut key = vec![0u8; key_len];
+            let mut iv = [0u8; 16];
+            let mut plaintext = vec![0u8; len];
+            fill_pseudo_random(&mut key, &mut state);
+            fill_pseudo_random(&mut iv, &mut state);
+            fill_pseudo_random(&mut plaintext, &mut state);
+
+            let padding = if len % 16 == 0 { None } else { Some("PKCS7") };
+            let mut padded = plaintext.clone();
+            if padding.is_some() {
+                pkcs7_pad(&mut padded, 16).unwrap();
+            }
+
+            // C_i = E(K, P_i ^ C_{i-1}) with C_0 = IV
+            let mut expected = Vec::new();
+            let mut previous = iv;
+            for block in padded.chunks_exact(16) {
+                let mut input = [0u8; 16];
+                for i in 0..16 {
+                    input[i] = block[i] ^ previous[i];
+                }
+                previous = aes_enc_block(&input, &key).unwrap();
+                expected.extend_from_slice(&previous);
+            }
+
+            assert_eq!(
+                aes_enc_cbc(&plaintext, &key, &iv, padding).unwrap(),
+                expected,
+                "key length {}, plaintext length {}",
+                key_len,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn test_cbc_encrypt_generic_identity_chaining() {
+    let iv = [0xa5u8; 16];
+    let mut plaintext = [0u8; 48];
+    for (i, byte) in plaintext.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+
+    let mut data = plaintext;
+    cbc_encrypt_generic(&mut data, &iv, |block| *block).unwrap();
+
+    // With the identity as block cipher, C_i = P_i ^ C_{i-1} and C_0 = IV
+    for i in 0..16 {
+        assert_eq!(data[i], plaintext[i] ^ iv[i]);
+        assert_eq!(data[16 + i], plaintext[16 + i] ^ data[i]);
+        assert_eq!(data[32 + i], plaintext[32 + i] ^ data[16 + i]);
+    }
+}
+
+#[test]
+fn test_cbc_decrypt_generic_inverts_encrypt_generic() {
+    let key = b"Very secret key.";
+    let iv = [0xa5u8; 16];
+    let plaintext = *b"0123456789abcdef0123456789abcdefSECRET SECRET!!!";
+
+    let mut data = plaintext;
+    cbc_encrypt_generic(&mut data, &iv, |block| aes_enc_block(block, key).unwrap()).unwrap();
+    assert_eq!(
+        data.to_vec(),
+        aes_enc_cbc(plaintext, key, &iv, None).unwrap()
+    );
+
+    cbc_decrypt_generic(&mut data, &iv, |block| aes_dec_block(block, key).unwrap()).unwrap();
+    assert_eq!(data, plaintext);
+
+    let mut partial = *b"0123456789abcdefSECRET!";
+    assert!(cbc_decrypt_generic(&mut partial, &iv, |block| *block).is_err());
+    assert_eq!(&partial, b"0123456789abcdefSECRET!");
+}
+
+#[test]
+fn test_aes_cbc_framed_round_trip() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+    let plaintext = b"Example plaintext.";
+
+    let framed = aes_enc_cbc_framed(plaintext, key, iv, Some("PKCS7")).unwrap();
+    let ciphertext = aes_enc_cbc(plaintext, key, iv, Some("PKCS7")).unwrap();
+
+    assert_eq!(framed[0], 16);
+    assert_eq!(&framed[1..17], iv);
+    assert_eq!(&framed[17..], &ciphertext[..]);
+
+    let decrypted = aes_dec_cbc_framed(&framed, key, Some("PKCS7")).unwrap();
+    assert_eq!(decrypted, plaintext);
+}
+
+#[test]
+fn test_aes_dec_cbc_framed_malformed() {
+    let key = b"Very secret key.";
+    let iv = b"Random Init Vec.";
+    let framed = aes_enc_cbc_framed(b"Example plaintext.", key, iv, Some("PKCS7")).unwrap();
+
+    // Empty frame
+    assert!(aes_dec_cbc_framed([], key, Some("PKCS7")).is_err());
+
+    // Length byte longer than the buffer
+    assert!(aes_dec_cbc_framed([0xff, 0x01, 0x02], key, Some("PKCS7")).is_err());
+
+    // Length byte other than the block size
+    let mut wrong_len = framed.clone();
+    wrong_len[0] = 8;
+    assert!(aes_dec_cbc_framed(&wrong_len, key, Some("PKCS7")).is_err());
+
+    // IV truncated
+    assert!(aes_dec_cbc_framed(&framed[..10], key, Some("PKCS7")).is_err());
+
+    // Ciphertext truncated to a partial block
+    assert!(aes_dec_cbc_framed(&framed[..framed.len() - 1], key, Some("PKCS7")).is_err());
+
+    // Frame holding only the IV decrypts to empty data without padding
+    assert_eq!(
+        aes_dec_cbc_framed(&framed[..17], key, None).unwrap(),
+        Vec::<u8>::new()
+    );
+}
+
+#[test]
+fn test_aes_cbc_explicit_pad_round_trip() {
+    let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+    let iv = [0x42u8; 16];
+    let data: Vec<u8> = (0..=40).collect();
+
+    for len in 0..=40 {
+        let plaintext = &data[..len];
+        let (ciphertext, pad_len) = aes_enc_cbc_explicit_pad(plaintext, &key, &iv).unwrap();
+
+        assert!((1..=16).contains(&pad_len), "length {}", len);
+        assert_eq!(ciphertext.len(), len + pad_len as usize);
+        assert_eq!(
+            ciphertext,
+            aes_enc_cbc(plaintext, &key, &iv, Some("PKCS7")).unwrap()
+        );
+
+        let decrypted = aes_dec_cbc_explicit_pad(&ciphertext, &key, &iv, pad_len).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+}
+
+#[test]
+fn test_aes_dec_cbc_explicit_pad_invalid_length() {
+    let key = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+    let iv = [0x42u8; 16];
+    let (ciphertext, _) = aes_enc_cbc_explicit_pad(b"Example", &key, &iv).unwrap();
+
+    assert!(aes_dec_cbc_explicit_pad(&ciphertext, &key, &iv, 0).is_err());
+    assert!(aes_dec_cbc_explicit_pad(&ciphertext, &key, &iv, 17).is_err());
+    assert!(aes_dec_cbc_explicit_pad([], &key, &iv, 1).is_err());
+}
+
+#[test]
+fn test_cbc_encrypt_generic_rejects_partial_block() {
+    let iv = [0xa5u8; 16];
+    let mut data = *b"0123456789abcdefSECRET!";
+
+    assert!(cbc_encrypt_generic(&mut data, &iv, |block| *block).is_err());
+    assert_eq!(&data, b"0123456789abcdefSECRET!");
+}
@@ -0,0 +1,124 @@
+use super::super::aes_ccm::*;
+use hex::decode as hex_decode;
+
+// The reference B0 blocks are taken from the "CBC IV in" values of the
+// RFC 3610 packet vectors.
+
+#[test]
+fn test_ccm_format_b0_rfc3610_packet_vector_1() {
+    let nonce = hex_decode("00000003020100A0A1A2A3A4A5").unwrap();
+    let b0 = ccm_format_b0(&nonce, 8, 23, 8).unwrap();
+
+    assert_eq!(
+        b0.to_vec(),
+        hex_decode("5900000003020100A0A1A2A3A4A50017").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_b0_rfc3610_packet_vector_2() {
+    let nonce = hex_decode("00000004030201A0A1A2A3A4A5").unwrap();
+    let b0 = ccm_format_b0(&nonce, 8, 24, 8).unwrap();
+
+    assert_eq!(
+        b0.to_vec(),
+        hex_decode("5900000004030201A0A1A2A3A4A50018").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_b0_rfc3610_packet_vector_4() {
+    let nonce = hex_decode("00000006050403A0A1A2A3A4A5").unwrap();
+    let b0 = ccm_format_b0(&nonce, 12, 19, 8).unwrap();
+
+    assert_eq!(
+        b0.to_vec(),
+        hex_decode("5900000006050403A0A1A2A3A4A50013").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_b0_rfc3610_packet_vector_7() {
+    let nonce = hex_decode("00000009080706A0A1A2A3A4A5").unwrap();
+    let b0 = ccm_format_b0(&nonce, 8, 23, 10).unwrap();
+
+    assert_eq!(
+        b0.to_vec(),
+        hex_decode("6100000009080706A0A1A2A3A4A50017").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_b0_without_aad() {
+    let nonce = hex_decode("101112131415161718191A1B").unwrap();
+    let b0 = ccm_format_b0(&nonce, 0, 0x010203, 16).unwrap();
+
+    // No Adata flag, M' = 7, L' = 2
+    assert_eq!(
+        b0.to_vec(),
+        hex_decode("3A101112131415161718191A1B010203").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_b0_invalid_nonce_length() {
+    assert!(ccm_format_b0(&[0u8; 6], 0, 0, 8).is_err());
+    assert!(ccm_format_b0(&[0u8; 14], 0, 0, 8).is_err());
+
+    let err = ccm_format_b0(&[0u8; 6], 0, 0, 8).unwrap_err();
+    let io_err = err.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_ccm_format_b0_invalid_tag_length() {
+    let nonce = [0u8; 13];
+    assert!(ccm_format_b0(&nonce, 0, 0, 2).is_err());
+    assert!(ccm_format_b0(&nonce, 0, 0, 7).is_err());
+    assert!(ccm_format_b0(&nonce, 0, 0, 18).is_err());
+}
+
+#[test]
+fn test_ccm_format_b0_payload_too_long() {
+    // A 13-byte nonce leaves a 2-byte length field
+    let nonce = [0u8; 13];
+    assert!(ccm_format_b0(&nonce, 0, 0xFFFF, 8).is_ok());
+    assert!(ccm_format_b0(&nonce, 0, 0x10000, 8).is_err());
+}
+
+#[test]
+fn test_ccm_format_aad_rfc3610_packet_vector_1() {
+    let aad = hex_decode("0001020304050607").unwrap();
+    let encoded = ccm_format_aad(&aad);
+
+    assert_eq!(
+        encoded,
+        hex_decode("00080001020304050607000000000000").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_aad_rfc3610_packet_vector_4() {
+    let aad = hex_decode("000102030405060708090A0B").unwrap();
+    let encoded = ccm_format_aad(&aad);
+
+    assert_eq!(
+        encoded,
+        hex_decode("000C000102030405060708090A0B0000").unwrap()
+    );
+}
+
+#[test]
+fn test_ccm_format_aad_empty() {
+    assert!(ccm_format_aad(&[]).is_empty());
+}
+
+#[test]
+fn test_ccm_format_aad_long_length_encoding() {
+    let aad = vec![0xAB; 0xFF00];
+    let encoded = ccm_format_aad(&aad);
+
+    assert_eq!(encoded[..6], [0xFF, 0xFE, 0x00, 0x00, 0xFF, 0x00]);
+    assert_eq!(encoded.len() % 16, 0);
+    assert_eq!(encoded.len(), 0xFF00 + 6 + 10);
+}
@@ -1,4 +1,6 @@
 use super::super::aes_cmac::*;
+use super::super::aes_core::aes_enc_block;
+use super::alloc_count::count_allocations;
 use hex::decode as hex_decode;
 
 #[test]
@@ -16,6 +18,49 @@ fn test_subkey_generation() {
     );
 }
 
+#[test]
+fn test_subkey_generation_192() {
+    // NIST SP 800-38B, Appendix D.2
+    let key = hex_decode("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").unwrap();
+    let l = aes_enc_block(&[0u8; 16], &key).unwrap();
+    let (k1, k2) = generate_subkey(&key).unwrap();
+
+    assert_eq!(
+        l.to_vec(),
+        hex_decode("22452d8e49a8a5939f7321ceea6d514b").unwrap()
+    );
+    assert_eq!(
+        k1.to_vec(),
+        hex_decode("448a5b1c93514b273ee6439dd4daa296").unwrap()
+    );
+    assert_eq!(
+        k2.to_vec(),
+        hex_decode("8914b63926a2964e7dcc873ba9b5452c").unwrap()
+    );
+}
+
+#[test]
+fn test_subkey_generation_256() {
+    // NIST SP 800-38B, Appendix D.3
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let l = aes_enc_block(&[0u8; 16], &key).unwrap();
+    let (k1, k2) = generate_subkey(&key).unwrap();
+
+    assert_eq!(
+        l.to_vec(),
+        hex_decode("e568f68194cf76d6174d4cc04310a854").unwrap()
+    );
+    assert_eq!(
+        k1.to_vec(),
+        hex_decode("cad1ed03299eedac2e9a99808621502f").unwrap()
+    );
+    assert_eq!(
+        k2.to_vec(),
+        hex_decode("95a3da06533ddb585d3533010c42a0d9").unwrap()
+    );
+}
+
 #[test]
 fn test_aes_cmac_128_example1() {
     let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
@@ -167,3 +212,549 @@ fn test_aes_cmac_invalid_key_length() {
         "AES-CMAC computation should fail with a specific error for a key of incorrect length."
     );
 }
+
+fn firmware_image() -> Vec<u8> {
+    (0..1000u32).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+#[test]
+fn test_firmware_mac_framing() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let image = firmware_image();
+
+    let mut framed = vec![0u8; 14];
+    framed.extend_from_slice(&[0x03, 0xE8]); // 1000 as a 128-bit big-endian length
+    framed.extend_from_slice(&image);
+
+    assert_eq!(
+        firmware_mac(&image, &key).unwrap(),
+        aes_cmac(&framed, &key).unwrap()
+    );
+}
+
+#[test]
+fn test_firmware_mac_does_not_copy_the_image() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let image = firmware_image();
+
+    let (tag, allocations) = count_allocations(|| firmware_mac(&image, &key).unwrap());
+    assert_eq!(allocations, 0);
+    assert!(verify_firmware_mac(&image, &key, &tag).unwrap());
+}
+
+#[test]
+fn test_verify_firmware_mac_valid() {
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let image = firmware_image();
+    let tag = firmware_mac(&image, &key).unwrap();
+
+    assert!(verify_firmware_mac(&image, &key, &tag).unwrap());
+
+    // Owned buffers and arrays are accepted as well
+    let key_array: [u8; 32] = key.clone().try_into().unwrap();
+    assert_eq!(firmware_mac(image.clone(), key_array).unwrap(), tag);
+    assert!(verify_firmware_mac(image, key, &tag).unwrap());
+}
+
+#[test]
+fn test_verify_firmware_mac_tampered_image() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let image = firmware_image();
+    let tag = firmware_mac(&image, &key).unwrap();
+
+    let mut tampered = image.clone();
+    tampered[500] ^= 0x01;
+    assert!(!verify_firmware_mac(&tampered, &key, &tag).unwrap());
+
+    let truncated = &image[..image.len() - 1];
+    assert!(!verify_firmware_mac(truncated, &key, &tag).unwrap());
+
+    let mut extended = image.clone();
+    extended.push(0x00);
+    assert!(!verify_firmware_mac(&extended, &key, &tag).unwrap());
+}
+
+#[test]
+fn test_verify_firmware_mac_tampered_tag() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let image = firmware_image();
+    let mut tag = firmware_mac(&image, &key).unwrap();
+    tag[15] ^= 0x80;
+
+    assert!(!verify_firmware_mac(&image, &key, &tag).unwrap());
+}
+
+#[test]
+fn test_firmware_mac_invalid_key() {
+    assert!(firmware_mac(firmware_image(), [0u8; 10]).is_err());
+}
+
+#[test]
+fn test_aes_cmac_accepts_arrays_and_vecs() {
+    let key: [u8; 16] = hex_decode("2b7e151628aed2a6abf7158809cf4f3c")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let message: [u8; 16] = hex_decode("6bc1bee22e409f96e93d7e117393172a")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    let message_vec = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+    let key_vec = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    let from_slices = aes_cmac(&message[..], &key[..]).unwrap();
+    let from_arrays = aes_cmac(message, key).unwrap();
+    let from_vecs = aes_cmac(message_vec, key_vec).unwrap();
+
+    assert_eq!(from_arrays, from_slices);
+    assert_eq!(from_vecs, from_slices);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_aes_cmac_mmap_matches_aes_cmac() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let content: Vec<u8> = (0..100_003u32).map(|i| (i % 251) as u8).collect();
+
+    let path = std::env::temp_dir().join(format!("soft-aes-cmac-mmap-{}", std::process::id()));
+    std::fs::write(&path, &content).unwrap();
+    let mac = aes_cmac_mmap(&path, &key);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(mac.unwrap(), aes_cmac(&content, &key).unwrap());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_aes_cmac_mmap_empty_file() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    let path =
+        std::env::temp_dir().join(format!("soft-aes-cmac-mmap-empty-{}", std::process::id()));
+    std::fs::write(&path, []).unwrap();
+    let mac = aes_cmac_mmap(&path, &key);
+    std::fs::remove_file(&path).unwrap();
+
+    // RFC 4493, Example 1
+    assert_eq!(
+        mac.unwrap().to_vec(),
+        hex_decode("bb1d6929e95937287fa37d129b756746").unwrap()
+    );
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_aes_cmac_mmap_missing_file() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let path = std::env::temp_dir().join("soft-aes-cmac-mmap-does-not-exist");
+
+    assert!(aes_cmac_mmap(path, key).is_err());
+}
+
+#[test]
+fn test_aes_cmac_mic32_is_truncated_cmac() {
+    // RFC 4493, Example 2: CMAC = 070a16b4 6b4d4144 f79bdd9d d04a287c
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+
+    let mic = aes_cmac_mic32(&message, &key).unwrap();
+    assert_eq!(mic.to_vec(), hex_decode("070a16b4").unwrap());
+
+    let full = aes_cmac(&message, &key).unwrap();
+    assert_eq!(mic, full[..4]);
+}
+
+#[test]
+fn test_verify_mic32() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+
+    assert!(verify_mic32(&message, &key, &[0x07, 0x0a, 0x16, 0xb4]).unwrap());
+    assert!(!verify_mic32(&message, &key, &[0x07, 0x0a, 0x16, 0xb5]).unwrap());
+    assert!(!verify_mic32(&message[1..], &key, &[0x07, 0x0a, 0x16, 0xb4]).unwrap());
+}
+
+#[test]
+fn test_aes_cmac_256_construction() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+
+    let tag = aes_cmac_256(&message, &key).unwrap();
+
+    let first = aes_cmac([&[0x01], &message[..]].concat(), &key).unwrap();
+    let second = aes_cmac([&[0x02], &message[..]].concat(), &key).unwrap();
+    assert_eq!(tag[..16], first);
+    assert_eq!(tag[16..], second);
+    assert_ne!(tag[..16], tag[16..]);
+
+    // Deterministic
+    assert_eq!(tag, aes_cmac_256(&message, &key).unwrap());
+}
+
+#[test]
+fn test_aes_cmac_256_distinct_inputs() {
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let messages: [&[u8]; 6] = [b"", b"\x00", b"\x01", b"\x02", b"message", &[0u8; 16]];
+
+    let tags: Vec<[u8; 32]> = messages
+        .iter()
+        .map(|message| aes_cmac_256(message, &key).unwrap())
+        .collect();
+
+    for (i, tag) in tags.iter().enumerate() {
+        assert_ne!(tag[..16], tag[16..]);
+        for other in &tags[i + 1..] {
+            assert_ne!(tag, other);
+        }
+    }
+}
+
+#[test]
+fn test_aes_cmac_boundary_lengths() {
+    // Prefixes of the RFC 4493 message covering the K1/K2 branch boundaries.
+    // Reference values computed with an independent AES-CMAC implementation;
+    // lengths 0, 16 and 64 match RFC 4493 Examples 1, 2 and 4.
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = hex_decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+
+    let cases = [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (1, "8e48c3c1d9f1c17c295c7aefd232bb14"),
+        (15, "f212d4c2154c8766de60c18c98fa0c93"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (17, "bc72cc168ec5a1434dcdb20bc1a2c2a4"),
+        (31, "8a157acff517d21bcd6ab65cd014cc70"),
+        (32, "ce0cbf1738f4df6428b1d93bf12081c9"),
+        (33, "cb8006fd4b9a8313333943ad6eb92797"),
+        (48, "c47c4d9d64588f67fb9de6fe745d7fbf"),
+        (63, "dfd14adbe2ad17d918ed36a674afb7d7"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ];
+
+    for (len, expected) in cases {
+        let mac = aes_cmac(&message[..len], &key).unwrap();
+        assert_eq!(
+            mac.to_vec(),
+            hex_decode(expected).unwrap(),
+            "length {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn test_aes_cmac_all_lengths_no_panic() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = [0xA5u8; 100];
+
+    for len in 0..=message.len() {
+        assert!(aes_cmac(&message[..len], &key).is_ok());
+    }
+}
+
+#[test]
+fn test_key_check_tag() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let tag = key_check_tag(&key).unwrap();
+
+    assert_eq!(
+        tag.to_vec(),
+        hex_decode("fbc37372383d331bc9594dd9ebd52f96").unwrap()
+    );
+    assert_eq!(tag, aes_cmac(b"SOFT-AES-KEY-CHECK", &key).unwrap());
+}
+
+#[test]
+fn test_key_check_tag_differs_per_key() {
+    let key_a = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let key_b = hex_decode("2b7e151628aed2a6abf7158809cf4f3d").unwrap();
+    let key_c =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+
+    let tag_a = key_check_tag(&key_a).unwrap();
+    assert_ne!(tag_a, key_check_tag(&key_b).unwrap());
+    assert_ne!(tag_a, key_check_tag(&key_c).unwrap());
+}
+
+#[test]
+fn test_verify_key_check() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let wrong_key = hex_decode("2b7e151628aed2a6abf7158809cf4f3d").unwrap();
+    let tag = key_check_tag(&key).unwrap();
+
+    assert!(verify_key_check(&key, &tag));
+    assert!(!verify_key_check(&wrong_key, &tag));
+    assert!(!verify_key_check(&key[..15], &tag));
+}
+
+#[test]
+fn test_aes_cmac_fields_framing() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let tag = aes_cmac_fields(&[b"ab", b"", b"c"], &key).unwrap();
+
+    let mut mac_input = vec![0, 0, 0, 2, b'a', b'b', 0, 0, 0, 0, 0, 0, 0, 1, b'c'];
+    assert_eq!(tag, aes_cmac(&mac_input, &key).unwrap());
+
+    mac_input.clear();
+    assert_eq!(
+        aes_cmac_fields(&[], &key).unwrap(),
+        aes_cmac(&mac_input, &key).unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_fields_does_not_allocate() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let image = firmware_image();
+
+    let (_, allocations) =
+        count_allocations(|| aes_cmac_fields(&[b"header", &image, b""], &key).unwrap());
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_aes_cmac_fields_distinguishes_groupings() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    // Naive concatenation cannot tell the groupings apart
+    assert_eq!(
+        aes_cmac([&b"ab"[..], b"c"].concat(), &key).unwrap(),
+        aes_cmac([&b"a"[..], b"bc"].concat(), &key).unwrap()
+    );
+
+    assert_ne!(
+        aes_cmac_fields(&[b"ab", b"c"], &key).unwrap(),
+        aes_cmac_fields(&[b"a", b"bc"], &key).unwrap()
+    );
+    assert_ne!(
+        aes_cmac_fields(&[b"abc"], &key).unwrap(),
+        aes_cmac_fields(&[b"abc", b""], &key).unwrap()
+    );
+}
+
+#[test]
+fn test_subkeys_match_generate_subkey() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let subkeys = SubKeys::from_key(&key).unwrap();
+    let (k1, k2) = generate_subkey(&key).unwrap();
+
+    assert_eq!(subkeys.k1(), &k1);
+    assert_eq!(subkeys.k2(), &k2);
+    assert!(SubKeys::from_key(&key[..15]).is_err());
+
+    let array_key: [u8; 16] = key.try_into().unwrap();
+    assert_eq!(SubKeys::from_key(array_key).unwrap().k1(), &k1);
+}
+
+#[test]
+fn test_subkeys_debug_is_redacted() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let subkeys = SubKeys::from_key(&key).unwrap();
+
+    let debug = format!("{:?}", subkeys);
+    assert_eq!(debug, r#"SubKeys { k1: "<redacted>", k2: "<redacted>" }"#);
+
+    let pretty = format!("{:#?}", subkeys);
+    assert!(pretty.contains("<redacted>"));
+    // A derived Debug would print K1 starting with 251 (0xfb)
+    assert!(!pretty.contains("251"));
+}
+
+#[test]
+fn test_aes_cmac_verify_flexible_length() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let message = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+    let tag = hex_decode("070a16b46b4d4144f79bdd9dd04a287c").unwrap();
+
+    for len in [16, 8, 4] {
+        assert!(aes_cmac_verify(&message, &key, &tag[..len]).unwrap());
+
+        let mut tampered = tag[..len].to_vec();
+        tampered[len - 1] ^= 0x01;
+        assert!(!aes_cmac_verify(&message, &key, &tampered).unwrap());
+        assert!(!aes_cmac_verify(&message[1..], &key, &tag[..len]).unwrap());
+    }
+}
+
+#[test]
+fn test_aes_cmac_verify_invalid_tag_length() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    assert!(aes_cmac_verify(b"message", &key, &[]).is_err());
+
+    let result = aes_cmac_verify(b"message", &key, &[0u8; 17]);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "AES CMAC ERROR: Expected tag must be 1 to 16 bytes, got 17 bytes"
+        );
+    }
+}
+
+#[test]
+fn test_cmac_struct_matches_rfc_4493_vectors() {
+    let message = hex_decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+    let cmac = Cmac::new(hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap()).unwrap();
+
+    // The same instance is reused across all four examples
+    for (len, expected) in [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (40, "dfa66747de9ae63030ca32611497c827"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ] {
+        assert_eq!(
+            cmac.mac(&message[..len]).to_vec(),
+            hex_decode(expected).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_cmac_struct_matches_aes_cmac() {
+    let message: Vec<u8> = (0..=255).collect();
+
+    for key_len in [16, 24, 32] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+        let cmac = Cmac::new(&key).unwrap();
+
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 255] {
+            assert_eq!(
+                cmac.mac(&message[..len]),
+                aes_cmac(&message[..len], &key).unwrap()
+            );
+        }
+    }
+
+    assert!(Cmac::new([0u8; 15]).is_err());
+}
+
+#[test]
+fn test_cmac_struct_is_shareable_and_redacted() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<Cmac>();
+
+    let cmac = Cmac::new([0x2bu8; 16]).unwrap();
+    let tag = std::thread::scope(|s| s.spawn(|| cmac.mac(b"message")).join().unwrap());
+    assert_eq!(tag, cmac.clone().mac(b"message"));
+
+    assert_eq!(
+        format!("{:?}", cmac),
+        r#"Cmac { expanded_key: "<redacted>", nr: 10, subkeys: SubKeys { k1: "<redacted>", k2: "<redacted>" } }"#
+    );
+}
+
+/// Run a `Mac` implementation through determinism and verification checks.
+pub(super) fn check_mac(mac: &(impl Mac + ?Sized)) {
+    let message = b"Example message.";
+    let tag = mac.mac(message);
+
+    assert_eq!(mac.mac(message), tag);
+    assert_ne!(mac.mac(b"Example message!"), tag);
+
+    assert!(mac.verify(message, &tag));
+    assert!(!mac.verify(b"Example message!", &tag));
+
+    let mut tampered = tag;
+    tampered[15] ^= 0x01;
+    assert!(!mac.verify(message, &tampered));
+    assert!(!mac.verify(message, &tag[..8]));
+}
+
+#[test]
+fn test_mac_trait_implementors() {
+    for key_len in [16, 24, 32] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+        let cmac = Cmac::new(&key).unwrap();
+
+        check_mac(&cmac);
+        assert_eq!(
+            Mac::mac(&cmac, b"message"),
+            aes_cmac(b"message", &key).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_aes_cmac_with_zero_run_matches_materialized() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let data: Vec<u8> = (1..=40).collect();
+
+    for prefix_len in [0, 1, 15, 16, 17] {
+        for zero_len in [0, 1, 15, 16, 17, 100] {
+            for suffix_len in [0, 5, 16, 23] {
+                let prefix = &data[..prefix_len];
+                let suffix = &data[40 - suffix_len..];
+
+                let mut materialized = prefix.to_vec();
+                materialized.resize(prefix_len + zero_len, 0x00);
+                materialized.extend_from_slice(suffix);
+
+                assert_eq!(
+                    aes_cmac_with_zero_run(prefix, zero_len, suffix, &key).unwrap(),
+                    aes_cmac(&materialized, &key).unwrap(),
+                    "prefix {}, zeros {}, suffix {}",
+                    prefix_len,
+                    zero_len,
+                    suffix_len
+                );
+            }
+        }
+    }
+
+    assert!(aes_cmac_with_zero_run(b"", 16, b"", &key[..15]).is_err());
+}
+
+#[test]
+fn test_cmac_last_block() {
+    // RFC 4493 subkeys for the key 2b7e151628aed2a6abf7158809cf4f3c
+    let k1: [u8; 16] = hex_decode("fbeed618357133667c85e08f7236a8de")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let k2: [u8; 16] = hex_decode("f7ddac306ae266ccf90bc11ee46d513b")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    // Full final block: M_last = M_n XOR K1
+    let full = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+    let mut expected = [0u8; 16];
+    for i in 0..16 {
+        expected[i] = full[i] ^ k1[i];
+    }
+    assert_eq!(cmac_last_block(&full, &k1, &k2), expected);
+
+    // Partial final block: M_last = (M_n || 10...0) XOR K2
+    let partial = hex_decode("30c81c46a35ce411").unwrap();
+    let mut padded = [0u8; 16];
+    padded[..8].copy_from_slice(&partial);
+    padded[8] = 0x80;
+    for i in 0..16 {
+        expected[i] = padded[i] ^ k2[i];
+    }
+    assert_eq!(cmac_last_block(&partial, &k1, &k2), expected);
+
+    // Empty message: M_last = 10...0 XOR K2
+    let mut expected = k2;
+    expected[0] ^= 0x80;
+    assert_eq!(cmac_last_block(&[], &k1, &k2), expected);
+}
+
+#[test]
+#[should_panic(expected = "AES CMAC ERROR: Last block must be at most 16 bytes")]
+fn test_cmac_last_block_too_long_panics() {
+    cmac_last_block(&[0u8; 17], &[0u8; 16], &[0u8; 16]);
+}
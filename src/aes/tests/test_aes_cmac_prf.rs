@@ -0,0 +1,115 @@
+use super::super::aes_cmac::*;
+use super::super::aes_cmac_prf::*;
+use hex::decode as hex_decode;
+
+// The reference values for the AES-CMAC-PRF-128 tests are taken from
+// RFC 4615, section 4.
+
+#[test]
+fn test_aes_cmac_prf_accepts_arrays() {
+    let key = [0x2bu8; 10];
+    let message = [0x6bu8; 20];
+
+    assert_eq!(
+        aes_cmac_prf_128(key, message).unwrap(),
+        aes_cmac_prf_128(&key[..], &message[..]).unwrap()
+    );
+    assert_eq!(
+        aes_cmac_prf_256(key, message).unwrap(),
+        aes_cmac_prf_256(&key[..], &message[..]).unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_128_key_len_18() {
+    let key = hex_decode("000102030405060708090a0b0c0d0e0fedcb").unwrap();
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+    let prv = aes_cmac_prf_128(&key, &message).unwrap();
+
+    assert_eq!(
+        prv.to_vec(),
+        hex_decode("84a348a4a45d235babfffc0d2b4da09a").unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_128_key_len_16() {
+    let key = hex_decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+    let prv = aes_cmac_prf_128(&key, &message).unwrap();
+
+    assert_eq!(
+        prv.to_vec(),
+        hex_decode("980ae87b5f4c9c5214f5b6a8455e4c2d").unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_128_key_len_10() {
+    let key = hex_decode("00010203040506070809").unwrap();
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+    let prv = aes_cmac_prf_128(&key, &message).unwrap();
+
+    assert_eq!(
+        prv.to_vec(),
+        hex_decode("290d9e112edb09ee141fcf64c0b72f3d").unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_128_empty_key() {
+    // The empty key is folded via the CMAC of the empty message under the
+    // all-zero key. Reference values computed with an independent AES-CMAC
+    // implementation.
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+
+    assert_eq!(
+        aes_cmac([], [0u8; 16]).unwrap().to_vec(),
+        hex_decode("4387c14b46ef7e176dceefa862d72ff9").unwrap()
+    );
+
+    let prv = aes_cmac_prf_128([], &message).unwrap();
+
+    assert_eq!(
+        prv.to_vec(),
+        hex_decode("98754e78d9fc6651decbb3e86d6d1e88").unwrap()
+    );
+    assert_eq!(aes_cmac_prf_128([], &message).unwrap(), prv);
+}
+
+#[test]
+fn test_aes_cmac_prf_256_reduces_to_aes_256_cmac() {
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let message = hex_decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+
+    assert_eq!(
+        aes_cmac_prf_256(&key, &message).unwrap(),
+        aes_cmac(&message, &key).unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_256_key_len_16() {
+    // Reference value computed independently from the documented folding
+    // construction.
+    let key = hex_decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+    let prv = aes_cmac_prf_256(&key, &message).unwrap();
+
+    assert_eq!(
+        prv.to_vec(),
+        hex_decode("9664f861e8b2f3387b2a7cfb8f7f5c96").unwrap()
+    );
+}
+
+#[test]
+fn test_aes_cmac_prf_256_differs_from_prf_128() {
+    let key = hex_decode("00010203040506070809").unwrap();
+    let message = hex_decode("000102030405060708090a0b0c0d0e0f10111213").unwrap();
+
+    assert_ne!(
+        aes_cmac_prf_256(&key, &message).unwrap(),
+        aes_cmac_prf_128(&key, &message).unwrap()
+    );
+}
@@ -0,0 +1,91 @@
+use super::super::aes_cbc::*;
+use super::super::aes_config::*;
+use super::super::aes_ecb::*;
+use hex::decode as hex_decode;
+
+const KEY: &[u8; 16] = b"Very secret key.";
+const IV: &[u8; 16] = b"Random Init Vec.";
+
+#[test]
+fn test_cbc_cipher_matches_free_functions() {
+    let plaintext = b"Example plaintext.";
+
+    for (padding, option) in [
+        (Padding::Pkcs7, Some("PKCS7")),
+        (Padding::Iso80, Some("0x80")),
+    ] {
+        let cipher = AesConfig::new(KEY)
+            .mode(Mode::Cbc)
+            .iv(IV)
+            .padding(padding)
+            .build()
+            .unwrap();
+
+        let encrypted = cipher.encrypt(plaintext).unwrap();
+        assert_eq!(encrypted, aes_enc_cbc(plaintext, KEY, IV, option).unwrap());
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn test_ecb_cipher_matches_free_functions() {
+    let key = hex_decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let plaintext = hex_decode("00112233445566778899aabbccddeeff").unwrap();
+
+    let cipher = AesConfig::new(&key).mode(Mode::Ecb).build().unwrap();
+    assert_eq!(cipher.padding(), Padding::None);
+
+    let encrypted = cipher.encrypt(&plaintext).unwrap();
+    assert_eq!(
+        encrypted,
+        hex_decode("69c4e0d86a7b0430d8cdb78070b4c55a").unwrap()
+    );
+    assert_eq!(encrypted, aes_enc_ecb(&plaintext, &key, None).unwrap());
+    assert_eq!(cipher.decrypt(&encrypted).unwrap(), plaintext);
+}
+
+#[test]
+fn test_build_rejects_missing_iv() {
+    let result = AesConfig::new(KEY).mode(Mode::Cbc).build();
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.to_string(), "AES CONFIG ERROR: CBC mode requires an IV");
+    }
+}
+
+#[test]
+fn test_build_rejects_mismatched_config() {
+    let result = AesConfig::new(KEY).mode(Mode::Ecb).iv(IV).build();
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "AES CONFIG ERROR: ECB mode does not use an IV"
+        );
+    }
+}
+
+#[test]
+fn test_build_rejects_missing_mode_and_invalid_key() {
+    let result = AesConfig::new(KEY).iv(IV).build();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "AES CONFIG ERROR: Mode of operation is not set"
+        );
+    }
+
+    assert!(AesConfig::new([0u8; 15]).mode(Mode::Ecb).build().is_err());
+}
+
+#[test]
+fn test_cipher_reports_mode_errors() {
+    let cipher = AesConfig::new(KEY).mode(Mode::Cbc).iv(IV).build().unwrap();
+
+    // Without padding the data must be block aligned
+    assert!(cipher.encrypt(b"not aligned").is_err());
+    assert_eq!(cipher.mode(), Mode::Cbc);
+}
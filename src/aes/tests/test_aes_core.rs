@@ -1,6 +1,9 @@
 use crate::aes::{
-    aes_dec_block, aes_enc_block, AES_128_KEY_SIZE, AES_192_KEY_SIZE, AES_256_KEY_SIZE,
-    AES_BLOCK_SIZE,
+    aes_dec_block, aes_dec_block_128, aes_dec_block_192, aes_dec_block_256, aes_dec_state,
+    aes_enc_block, aes_enc_block_128, aes_enc_block_192, aes_enc_block_256, aes_enc_block_expanded,
+    aes_enc_block_typed, aes_enc_state, aes_expand_key, dump_round_keys, expanded_key_hex,
+    expanded_key_len, is_valid_key_len, num_rounds, num_rounds_for_key_len, split_key,
+    transpose_block, Block16, AES_128_KEY_SIZE, AES_192_KEY_SIZE, AES_256_KEY_SIZE, AES_BLOCK_SIZE,
 };
 
 // The reference values for these test cases are taken from CryptoTool's
@@ -137,3 +140,413 @@ fn test_aes_enc_block_invalid_key_length() {
         "AES CORE ERROR: Invalid key length. Expected 16, 24, or 32 bytes, got 15 bytes"
     );
 }
+
+#[test]
+fn test_num_rounds() {
+    assert_eq!(num_rounds(&[0u8; AES_128_KEY_SIZE]).unwrap(), 10);
+    assert_eq!(num_rounds(&[0u8; AES_192_KEY_SIZE]).unwrap(), 12);
+    assert_eq!(num_rounds(&[0u8; AES_256_KEY_SIZE]).unwrap(), 14);
+}
+
+#[test]
+fn test_expanded_key_len() {
+    assert_eq!(expanded_key_len(AES_128_KEY_SIZE).unwrap(), 176);
+    assert_eq!(expanded_key_len(AES_192_KEY_SIZE).unwrap(), 208);
+    assert_eq!(expanded_key_len(AES_256_KEY_SIZE).unwrap(), 240);
+}
+
+#[test]
+fn test_expanded_key_len_invalid_key_length() {
+    assert!(expanded_key_len(0).is_err());
+    assert!(expanded_key_len(17).is_err());
+    assert!(expanded_key_len(64).is_err());
+}
+
+#[test]
+fn test_num_rounds_invalid_key_length() {
+    assert!(num_rounds(&[]).is_err());
+    assert!(num_rounds(&[0u8; 15]).is_err());
+    assert!(num_rounds(&[0u8; 20]).is_err());
+    assert!(num_rounds(&[0u8; 33]).is_err());
+}
+
+#[test]
+fn test_aes_enc_dec_state_fips_197_appendix_b() {
+    // Expanded key for 2b7e151628aed2a6abf7158809cf4f3c from FIPS-197,
+    // Appendix A.1. The unused tail of the buffer stays zero.
+    let mut expanded_key = [0u8; 240];
+    expanded_key[..176].copy_from_slice(
+        &hex::decode(
+            "2b7e151628aed2a6abf7158809cf4f3ca0fafe1788542cb123a339392a6c7605\
+             f2c295f27a96b9435935807a7359f67f3d80477d4716fe3e1e237e446d7a883b\
+             ef44a541a8525b7fb671253bdb0bad00d4d1c6f87c839d87caf2b8bc11f915bc\
+             6d88a37a110b3efddbf98641ca0093fd4e54f70e5f5fc9f384a64fb24ea6dc4f\
+             ead27321b58dbad2312bf5607f8d292fac7766f319fadc2128d12941575c006e\
+             d014f9a8c9ee2589e13f0cc8b6630ca6",
+        )
+        .unwrap(),
+    );
+
+    // Input and output states from FIPS-197, Appendix B
+    let input_state: [[u8; 4]; 4] = [
+        [0x32, 0x88, 0x31, 0xe0],
+        [0x43, 0x5a, 0x31, 0x37],
+        [0xf6, 0x30, 0x98, 0x07],
+        [0xa8, 0x8d, 0xa2, 0x34],
+    ];
+    let output_state: [[u8; 4]; 4] = [
+        [0x39, 0x02, 0xdc, 0x19],
+        [0x25, 0xdc, 0x11, 0x6a],
+        [0x84, 0x09, 0x85, 0x0b],
+        [0x1d, 0xfb, 0x97, 0x32],
+    ];
+
+    let mut state = input_state;
+    aes_enc_state(&mut state, &expanded_key, 10);
+    assert_eq!(state, output_state);
+
+    aes_dec_state(&mut state, &expanded_key, 10);
+    assert_eq!(state, input_state);
+}
+
+#[test]
+fn test_split_key_valid_lengths() {
+    for half_len in [AES_128_KEY_SIZE, AES_192_KEY_SIZE, AES_256_KEY_SIZE] {
+        let key: Vec<u8> = (0..2 * half_len as u8).collect();
+        let (first, second) = split_key(&key).expect("Split failed");
+
+        assert_eq!(first.len(), half_len);
+        assert_eq!(second.len(), half_len);
+        assert_eq!(first, &key[..half_len]);
+        assert_eq!(second, &key[half_len..]);
+    }
+}
+
+#[test]
+fn test_split_key_invalid_lengths() {
+    for len in [0, 16, 31, 33, 47, 63, 65, 96] {
+        let key = vec![0u8; len];
+        assert!(split_key(&key).is_err(), "length {} was accepted", len);
+    }
+}
+
+#[test]
+fn test_expanded_key_hex_fips_197_appendix_a1() {
+    // Key expansion of the AES-128 key from FIPS-197, Appendix A.1
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let expected = concat!(
+        "2b7e151628aed2a6abf7158809cf4f3ca0fafe1788542cb123a339392a6c7605",
+        "f2c295f27a96b9435935807a7359f67f3d80477d4716fe3e1e237e446d7a883b",
+        "ef44a541a8525b7fb671253bdb0bad00d4d1c6f87c839d87caf2b8bc11f915bc",
+        "6d88a37a110b3efddbf98641ca0093fd4e54f70e5f5fc9f384a64fb24ea6dc4f",
+        "ead27321b58dbad2312bf5607f8d292fac7766f319fadc2128d12941575c006e",
+        "d014f9a8c9ee2589e13f0cc8b6630ca6",
+    );
+
+    assert_eq!(expanded_key_hex(&key).unwrap(), expected);
+
+    // 13 and 15 round keys for AES-192 and AES-256
+    assert_eq!(expanded_key_hex(&[0u8; 24]).unwrap().len(), 13 * 32);
+    assert_eq!(expanded_key_hex(&[0u8; 32]).unwrap().len(), 15 * 32);
+    assert!(expanded_key_hex(&[0u8; 15]).is_err());
+}
+
+#[test]
+fn test_dump_round_keys_fips_197_appendix_a() {
+    // Key, number of round keys, first, second and last round key from
+    // FIPS-197, Appendix A.1 - A.3
+    let cases = [
+        (
+            "2b7e151628aed2a6abf7158809cf4f3c",
+            11,
+            "2b7e151628aed2a6abf7158809cf4f3c",
+            "a0fafe1788542cb123a339392a6c7605",
+            "d014f9a8c9ee2589e13f0cc8b6630ca6",
+        ),
+        (
+            "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b",
+            13,
+            "8e73b0f7da0e6452c810f32b809079e5",
+            "62f8ead2522c6b7bfe0c91f72402f5a5",
+            "e98ba06f448c773c8ecc720401002202",
+        ),
+        (
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+            15,
+            "603deb1015ca71be2b73aef0857d7781",
+            "1f352c073b6108d72d9810a30914dff4",
+            "fe4890d1e6188d0b046df344706c631e",
+        ),
+    ];
+
+    for (key, rounds, first, second, last) in cases {
+        let mut out = String::new();
+        dump_round_keys(&hex::decode(key).unwrap(), &mut out).unwrap();
+
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), rounds);
+        assert_eq!(lines[0], format!("Round 0: {}", first));
+        assert_eq!(lines[1], format!("Round 1: {}", second));
+        assert_eq!(lines[rounds - 1], format!("Round {}: {}", rounds - 1, last));
+    }
+}
+
+#[test]
+fn test_dump_round_keys_invalid_key() {
+    let mut out = String::new();
+    assert!(dump_round_keys(&[0u8; 20], &mut out).is_err());
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_aes_enc_block_fixed_key_matches_aes_enc_block() {
+    // FIPS-197, Appendix C
+    let plaintext: [u8; AES_BLOCK_SIZE] = hex::decode("00112233445566778899aabbccddeeff")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let key: Vec<u8> = (0..32).collect();
+
+    let key_128: [u8; AES_128_KEY_SIZE] = key[..16].try_into().unwrap();
+    let key_192: [u8; AES_192_KEY_SIZE] = key[..24].try_into().unwrap();
+    let key_256: [u8; AES_256_KEY_SIZE] = key[..32].try_into().unwrap();
+
+    let ciphertext_128 = aes_enc_block_128(&plaintext, &key_128);
+    let ciphertext_192 = aes_enc_block_192(&plaintext, &key_192);
+    let ciphertext_256 = aes_enc_block_256(&plaintext, &key_256);
+
+    assert_eq!(
+        ciphertext_128.to_vec(),
+        hex::decode("69c4e0d86a7b0430d8cdb78070b4c55a").unwrap()
+    );
+    assert_eq!(
+        ciphertext_192.to_vec(),
+        hex::decode("dda97ca4864cdfe06eaf70a0ec0d7191").unwrap()
+    );
+    assert_eq!(
+        ciphertext_256.to_vec(),
+        hex::decode("8ea2b7ca516745bfeafc49904b496089").unwrap()
+    );
+
+    assert_eq!(ciphertext_128, aes_enc_block(&plaintext, &key_128).unwrap());
+    assert_eq!(ciphertext_192, aes_enc_block(&plaintext, &key_192).unwrap());
+    assert_eq!(ciphertext_256, aes_enc_block(&plaintext, &key_256).unwrap());
+}
+
+#[test]
+fn test_aes_dec_block_fixed_key_inverts_encryption() {
+    let ciphertext: [u8; AES_BLOCK_SIZE] = hex::decode("69c4e0d86a7b0430d8cdb78070b4c55a")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let key: Vec<u8> = (0..32).collect();
+
+    let key_128: [u8; AES_128_KEY_SIZE] = key[..16].try_into().unwrap();
+    let key_192: [u8; AES_192_KEY_SIZE] = key[..24].try_into().unwrap();
+    let key_256: [u8; AES_256_KEY_SIZE] = key[..32].try_into().unwrap();
+
+    assert_eq!(
+        aes_dec_block_128(&ciphertext, &key_128).to_vec(),
+        hex::decode("00112233445566778899aabbccddeeff").unwrap()
+    );
+    assert_eq!(
+        aes_dec_block_128(&ciphertext, &key_128),
+        aes_dec_block(&ciphertext, &key_128).unwrap()
+    );
+    assert_eq!(
+        aes_dec_block_192(&ciphertext, &key_192),
+        aes_dec_block(&ciphertext, &key_192).unwrap()
+    );
+    assert_eq!(
+        aes_dec_block_256(&ciphertext, &key_256),
+        aes_dec_block(&ciphertext, &key_256).unwrap()
+    );
+}
+
+#[cfg(feature = "research")]
+#[test]
+fn test_aes_block_with_standard_sbox_matches_aes() {
+    use crate::aes::{aes_dec_block_with_sbox, aes_enc_block_with_sbox, standard_sboxes};
+
+    let (sbox, inv_sbox) = standard_sboxes();
+    let plaintext: [u8; AES_BLOCK_SIZE] = hex::decode("00112233445566778899aabbccddeeff")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    for key_len in [AES_128_KEY_SIZE, AES_192_KEY_SIZE, AES_256_KEY_SIZE] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+
+        let ciphertext = aes_enc_block_with_sbox(&plaintext, &key, &sbox, &inv_sbox).unwrap();
+        assert_eq!(ciphertext, aes_enc_block(&plaintext, &key).unwrap());
+
+        let decrypted = aes_dec_block_with_sbox(&ciphertext, &key, &sbox, &inv_sbox).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+}
+
+#[cfg(feature = "research")]
+#[test]
+fn test_aes_block_with_modified_sbox() {
+    use crate::aes::{aes_dec_block_with_sbox, aes_enc_block_with_sbox, standard_sboxes};
+
+    let (mut sbox, mut inv_sbox) = standard_sboxes();
+
+    // Swap two S-box entries and fix up the inverse accordingly
+    sbox.swap(0x00, 0x01);
+    inv_sbox[sbox[0x00] as usize] = 0x00;
+    inv_sbox[sbox[0x01] as usize] = 0x01;
+
+    let plaintext = [0u8; AES_BLOCK_SIZE];
+    let key = [0u8; AES_128_KEY_SIZE];
+
+    let ciphertext = aes_enc_block_with_sbox(&plaintext, &key, &sbox, &inv_sbox).unwrap();
+    assert_ne!(ciphertext, aes_enc_block(&plaintext, &key).unwrap());
+
+    let decrypted = aes_dec_block_with_sbox(&ciphertext, &key, &sbox, &inv_sbox).unwrap();
+    assert_eq!(decrypted, plaintext);
+}
+
+#[cfg(feature = "research")]
+#[test]
+fn test_aes_block_with_sbox_rejects_mismatched_inverse() {
+    use crate::aes::{aes_enc_block_with_sbox, standard_sboxes};
+
+    let (mut sbox, inv_sbox) = standard_sboxes();
+    sbox.swap(0x10, 0x20);
+
+    let result = aes_enc_block_with_sbox(&[0u8; 16], &[0u8; 16], &sbox, &inv_sbox);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_block16_try_from() {
+    let bytes: Vec<u8> = (0..16).collect();
+
+    let block = Block16::try_from(bytes.clone()).unwrap();
+    assert_eq!(block, Block16::try_from(bytes.as_slice()).unwrap());
+    assert_eq!(block.as_ref(), bytes.as_slice());
+    assert_eq!(<[u8; 16]>::from(block).to_vec(), bytes);
+}
+
+#[test]
+fn test_block16_try_from_invalid_length() {
+    for len in [0, 15, 17, 32] {
+        let result = Block16::try_from(vec![0u8; len]);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(
+                e.to_string(),
+                format!(
+                    "AES CORE ERROR: Invalid block length. Expected 16 bytes, got {} bytes",
+                    len
+                )
+            );
+            assert_eq!(
+                e.downcast_ref::<std::io::Error>().unwrap().kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+    }
+}
+
+#[test]
+fn test_aes_enc_block_typed_matches_slice_api() {
+    let block = [
+        0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07,
+        0x34,
+    ];
+
+    let key_128: [u8; 16] = std::array::from_fn(|i| i as u8);
+    let key_192: [u8; 24] = std::array::from_fn(|i| i as u8);
+    let key_256: [u8; 32] = std::array::from_fn(|i| i as u8);
+
+    assert_eq!(
+        aes_enc_block_typed(&block, &key_128),
+        aes_enc_block(&block, &key_128).unwrap()
+    );
+    assert_eq!(
+        aes_enc_block_typed(&block, &key_192),
+        aes_enc_block(&block, &key_192).unwrap()
+    );
+    assert_eq!(
+        aes_enc_block_typed(&block, &key_256),
+        aes_enc_block(&block, &key_256).unwrap()
+    );
+}
+
+#[test]
+fn test_aes_key_bytes_parameters() {
+    use crate::aes::AesKeyBytes;
+
+    assert_eq!(
+        (<[u8; 16]>::NK, <[u8; 16]>::NR),
+        (4, num_rounds(&[0u8; 16]).unwrap())
+    );
+    assert_eq!(
+        (<[u8; 24]>::NK, <[u8; 24]>::NR),
+        (6, num_rounds(&[0u8; 24]).unwrap())
+    );
+    assert_eq!(
+        (<[u8; 32]>::NK, <[u8; 32]>::NR),
+        (8, num_rounds(&[0u8; 32]).unwrap())
+    );
+}
+
+#[test]
+fn test_aes_enc_block_expanded_matches_aes_enc_block() {
+    let block = [
+        0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07,
+        0x34,
+    ];
+
+    for key_len in [AES_128_KEY_SIZE, AES_192_KEY_SIZE, AES_256_KEY_SIZE] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+        let (expanded_key, nr) = aes_expand_key(&key).unwrap();
+
+        assert_eq!(nr, num_rounds(&key).unwrap());
+        assert_eq!(
+            aes_enc_block_expanded(&block, &expanded_key, nr),
+            aes_enc_block(&block, &key).unwrap()
+        );
+    }
+
+    assert!(aes_expand_key(&[0u8; 20]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Invalid number of rounds")]
+fn test_aes_enc_block_expanded_rejects_invalid_rounds() {
+    let (expanded_key, _) = aes_expand_key(&[0u8; 16]).unwrap();
+    aes_enc_block_expanded(&[0u8; 16], &expanded_key, 11);
+}
+
+#[test]
+fn test_transpose_block_round_trip() {
+    // FIPS-197, Appendix B input block, whose state is shown row by row there
+    let block: [u8; AES_BLOCK_SIZE] = hex::decode("3243f6a8885a308d313198a2e0370734")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    let transposed = transpose_block(&block);
+    assert_eq!(
+        transposed.to_vec(),
+        hex::decode("328831e0435a3137f6309807a88da234").unwrap()
+    );
+    assert_eq!(transpose_block(&transposed), block);
+}
+
+// Both helpers must be usable in a const context
+const _: () = assert!(is_valid_key_len(AES_128_KEY_SIZE) && !is_valid_key_len(20));
+const _: () = assert!(matches!(num_rounds_for_key_len(AES_256_KEY_SIZE), Some(14)));
+
+#[test]
+fn test_const_key_len_helpers_agree_with_runtime_validation() {
+    for key_len in 0..=64 {
+        let key = vec![0u8; key_len];
+
+        assert_eq!(is_valid_key_len(key_len), num_rounds(&key).is_ok());
+        assert_eq!(num_rounds_for_key_len(key_len), num_rounds(&key).ok());
+    }
+}
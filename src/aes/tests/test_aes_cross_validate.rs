@@ -0,0 +1,78 @@
+// Differential tests against the RustCrypto `aes` crate.
+//
+// Random blocks are encrypted and decrypted with both implementations for
+// every key size. The `aes` crate is a dev-dependency only; these tests run
+// with `cargo test --features cross-validate`.
+
+use super::super::aes_core::*;
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+
+const BLOCKS_PER_KEY_SIZE: usize = 4096;
+
+/// Fill `buf` with deterministic pseudo-random bytes (xorshift64).
+fn fill_pseudo_random(buf: &mut [u8], state: &mut u64) {
+    for byte in buf.iter_mut() {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *byte = *state as u8;
+    }
+}
+
+fn cross_validate<C>(key_len: usize, seed: u64)
+where
+    C: KeyInit + BlockEncrypt + BlockDecrypt,
+{
+    let mut state = seed;
+    let mut key = vec![0u8; key_len];
+
+    for i in 0..BLOCKS_PER_KEY_SIZE {
+        // Use a fresh key every 64 blocks to cover many key schedules
+        if i % 64 == 0 {
+            fill_pseudo_random(&mut key, &mut state);
+        }
+        let reference = C::new_from_slice(&key).expect("Invalid key length");
+
+        let mut block = [0u8; AES_BLOCK_SIZE];
+        fill_pseudo_random(&mut block, &mut state);
+
+        let ciphertext = aes_enc_block(&block, &key).expect("Encryption failed");
+        let mut expected = GenericArray::clone_from_slice(&block);
+        reference.encrypt_block(&mut expected);
+        assert_eq!(
+            ciphertext[..],
+            expected[..],
+            "encryption mismatch, key {:02x?}, block {:02x?}",
+            key,
+            block
+        );
+
+        let plaintext = aes_dec_block(&block, &key).expect("Decryption failed");
+        let mut expected = GenericArray::clone_from_slice(&block);
+        reference.decrypt_block(&mut expected);
+        assert_eq!(
+            plaintext[..],
+            expected[..],
+            "decryption mismatch, key {:02x?}, block {:02x?}",
+            key,
+            block
+        );
+    }
+}
+
+#[test]
+fn test_cross_validate_aes_128() {
+    cross_validate::<aes::Aes128>(16, 0x0123_4567_89ab_cdef);
+}
+
+#[test]
+fn test_cross_validate_aes_192() {
+    cross_validate::<aes::Aes192>(24, 0xfedc_ba98_7654_3210);
+}
+
+#[test]
+fn test_cross_validate_aes_256() {
+    cross_validate::<aes::Aes256>(32, 0x0f1e_2d3c_4b5a_6978);
+}
@@ -0,0 +1,168 @@
+use super::super::aes_ctr::*;
+use hex::decode as hex_decode;
+
+#[test]
+fn test_increment_counter_big_endian() {
+    let mut block = [0u8; 16];
+    increment_counter(&mut block, 4, Endian::Big).unwrap();
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("00000000000000000000000000000001").unwrap()
+    );
+}
+
+#[test]
+fn test_increment_counter_big_endian_carry() {
+    let mut block: [u8; 16] = hex_decode("000102030405060708090A0B00FFFFFF")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    increment_counter(&mut block, 4, Endian::Big).unwrap();
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("000102030405060708090A0B01000000").unwrap()
+    );
+}
+
+#[test]
+fn test_increment_counter_width_4_wraparound() {
+    let mut block: [u8; 16] = hex_decode("CAFEBABECAFEBABECAFEBABEFFFFFFFF")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    increment_counter(&mut block, 4, Endian::Big).unwrap();
+
+    // The counter wraps within its 4 bytes and the nonce part is untouched
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("CAFEBABECAFEBABECAFEBABE00000000").unwrap()
+    );
+}
+
+#[test]
+fn test_increment_counter_width_4_wraparound_little_endian() {
+    let mut block: [u8; 16] = hex_decode("CAFEBABECAFEBABECAFEBABEFFFFFFFF")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    increment_counter(&mut block, 4, Endian::Little).unwrap();
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("CAFEBABECAFEBABECAFEBABE00000000").unwrap()
+    );
+}
+
+#[test]
+fn test_increment_counter_little_endian_carry() {
+    let mut block: [u8; 16] = hex_decode("000102030405060708090A0BFFFF0000")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    increment_counter(&mut block, 4, Endian::Little).unwrap();
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("000102030405060708090A0B00000100").unwrap()
+    );
+}
+
+#[test]
+fn test_increment_counter_width_16_full_block() {
+    let mut block: [u8; 16] = hex_decode("00FFFFFFFFFFFFFFFFFFFFFFFFFFFFFF")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    increment_counter(&mut block, 16, Endian::Big).unwrap();
+    assert_eq!(
+        block.to_vec(),
+        hex_decode("01000000000000000000000000000000").unwrap()
+    );
+
+    let mut block = [0xFFu8; 16];
+    increment_counter(&mut block, 16, Endian::Big).unwrap();
+    assert_eq!(block, [0u8; 16]);
+}
+
+#[test]
+fn test_increment_counter_invalid_width() {
+    let mut block = [0u8; 16];
+    assert!(increment_counter(&mut block, 0, Endian::Big).is_err());
+    assert!(increment_counter(&mut block, 17, Endian::Little).is_err());
+    assert_eq!(block, [0u8; 16]);
+}
+
+const WINDOW_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const WINDOW_NONCE: [u8; 8] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7];
+
+#[test]
+fn test_aes_enc_ctr_windowed_known_answer() {
+    let key = hex_decode(WINDOW_KEY).unwrap();
+    let data: Vec<u8> = (0..80).collect();
+
+    // Computed with a reference CTR implementation, restarting at each window
+    let expected = hex_decode(
+        "0c2eb9b55edc612d11f7f73e97fe450dbfbe8ee1fecc7b24f8bcdbcd559caed1\
+         717ebe7ec2e005162cc7ff302f46ce4ee54d6d91c2225228cd42948dc0c88bb3\
+         d6683b3ca25321bd54c95e1c14dcdac7",
+    )
+    .unwrap();
+
+    let ciphertext = aes_enc_ctr_windowed(&data, &key, &WINDOW_NONCE, 32).unwrap();
+    assert_eq!(ciphertext, expected);
+    assert_eq!(
+        aes_dec_ctr_windowed(&ciphertext, &key, &WINDOW_NONCE, 32).unwrap(),
+        data
+    );
+}
+
+#[test]
+fn test_aes_ctr_window_matches_full_encryption() {
+    let key = hex_decode(WINDOW_KEY).unwrap();
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+
+    for window_size in [1, 15, 16, 100, 256, 1000, 4096] {
+        let ciphertext = aes_enc_ctr_windowed(&data, &key, &WINDOW_NONCE, window_size).unwrap();
+
+        for (index, window) in ciphertext.chunks(window_size).enumerate() {
+            let start = index * window_size;
+            let plaintext = aes_ctr_window(window, &key, &WINDOW_NONCE, index as u32).unwrap();
+            assert_eq!(plaintext, data[start..start + window.len()]);
+        }
+    }
+}
+
+#[test]
+fn test_aes_enc_ctr_windowed_invalid_input() {
+    let key = hex_decode(WINDOW_KEY).unwrap();
+
+    assert!(aes_enc_ctr_windowed(b"data", &key, &WINDOW_NONCE, 0).is_err());
+    assert!(aes_enc_ctr_windowed(b"data", &key[..15], &WINDOW_NONCE, 16).is_err());
+    assert!(aes_ctr_window(b"data", &key[..15], &WINDOW_NONCE, 0).is_err());
+    assert!(aes_enc_ctr_windowed([], &key, &WINDOW_NONCE, 16)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_xor_keystream_partial_block() {
+    let keystream: Vec<u8> = (0x10..0x20).collect();
+    let mut data = [0xFFu8; 5];
+
+    xor_keystream(&mut data, &keystream);
+
+    // Only the first five keystream bytes are used
+    assert_eq!(data, [0xEF, 0xEE, 0xED, 0xEC, 0xEB]);
+
+    // XOR with the same keystream restores the data
+    xor_keystream(&mut data, &keystream);
+    assert_eq!(data, [0xFFu8; 5]);
+
+    let mut empty: [u8; 0] = [];
+    xor_keystream(&mut empty, &[]);
+}
+
+#[test]
+#[should_panic(expected = "AES CTR ERROR: Keystream of 4 bytes is shorter")]
+fn test_xor_keystream_short_keystream_panics() {
+    let mut data = [0u8; 5];
+    xor_keystream(&mut data, &[0u8; 4]);
+}
@@ -1,3 +1,4 @@
+use super::super::aes_core::aes_enc_block;
 use super::super::aes_ecb::*;
 use hex::decode as hex_decode;
 
@@ -18,7 +19,7 @@ fn test_aes_enc_ecb_no_padding() {
     ];
 
     // Call the encryption function
-    let ciphertext = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+    let ciphertext = aes_enc_ecb(plaintext, key, None).expect("Encryption failed");
 
     // Assert that the produced ciphertext matches the expected ciphertext
     assert_eq!(
@@ -45,7 +46,7 @@ fn test_aes_dec_ecb_no_padding() {
     ];
 
     // Call the decryption function
-    let decrypted_plaintext = aes_dec_ecb(&ciphertext, &key, None).expect("Decryption failed");
+    let decrypted_plaintext = aes_dec_ecb(ciphertext, key, None).expect("Decryption failed");
 
     // Assert that the decrypted plaintext matches the expected plaintext
     assert_eq!(
@@ -75,7 +76,7 @@ fn test_aes_enc_ecb_with_pkcs7_padding() {
     ];
 
     // Call the encryption function with PKCS7 padding
-    let ciphertext = aes_enc_ecb(&plaintext, &key, Some("PKCS7")).expect("Encryption failed");
+    let ciphertext = aes_enc_ecb(plaintext, key, Some("PKCS7")).expect("Encryption failed");
 
     // Assert that the produced ciphertext matches the expected ciphertext
     assert_eq!(
@@ -104,7 +105,7 @@ fn test_aes_dec_ecb_with_pkcs7_padding_removal() {
     ];
 
     // Call the decryption function with PKCS7 padding removal
-    let plaintext = aes_dec_ecb(&ciphertext, &key, Some("PKCS7")).expect("Decryption failed");
+    let plaintext = aes_dec_ecb(ciphertext, key, Some("PKCS7")).expect("Decryption failed");
 
     // Assert that the decrypted plaintext matches the expected plaintext
     assert_eq!(
@@ -143,3 +144,218 @@ fn test_aes_dec_ecb_with_80_padding_removal() {
         "Decrypted plaintext does not match expected value with 0x80 padding removal"
     );
 }
+
+#[test]
+fn test_aes_dec_ecb_error_invalid_ciphertext_length() {
+    let ciphertext = [0u8; 33]; // Length not a multiple of AES_BLOCK_SIZE
+    let key = [0u8; 16];
+
+    let result = aes_dec_ecb(ciphertext, key, None);
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "AES DEC ECB Error: Ciphertext must be a multiple of AES_BLOCK_SIZE, got 33 bytes"
+        );
+    }
+}
+
+#[test]
+fn test_encrypt_blocks_iter_matches_aes_enc_ecb() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext =
+        hex_decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51").unwrap();
+
+    let blocks = plaintext
+        .chunks_exact(16)
+        .map(|chunk| <[u8; 16]>::try_from(chunk).unwrap());
+    let encrypted: Vec<u8> = encrypt_blocks_iter(blocks, &key)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Encryption failed")
+        .concat();
+
+    let expected = aes_enc_ecb(&plaintext, &key, None).expect("Encryption failed");
+    assert_eq!(encrypted, expected);
+}
+
+#[test]
+fn test_encrypt_blocks_iter_is_lazy() {
+    use std::cell::Cell;
+
+    let key = b"Very secret key.";
+    let pulled = Cell::new(0);
+    let blocks = (0..3u8).map(|i| {
+        pulled.set(pulled.get() + 1);
+        [i; 16]
+    });
+
+    let mut iter = encrypt_blocks_iter(blocks, key);
+    assert_eq!(pulled.get(), 0);
+
+    iter.next().unwrap().expect("Encryption failed");
+    assert_eq!(pulled.get(), 1);
+
+    assert_eq!(iter.count(), 2);
+    assert_eq!(pulled.get(), 3);
+}
+
+#[test]
+fn test_encrypt_blocks_iter_invalid_key() {
+    let key = [0u8; 15];
+    let mut iter = encrypt_blocks_iter(vec![[0u8; 16]; 2].into_iter(), key);
+
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_aes_enc_dec_blocks_in_place_matches_aes_enc_ecb() {
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let plaintext = hex_decode(
+        "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+         30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+    )
+    .unwrap();
+
+    let mut data = plaintext.clone();
+    aes_enc_blocks_in_place(&mut data, &key).expect("Encryption failed");
+    assert_eq!(data, aes_enc_ecb(&plaintext, &key, None).unwrap());
+
+    aes_dec_blocks_in_place(&mut data, &key).expect("Decryption failed");
+    assert_eq!(data, plaintext);
+}
+
+#[test]
+fn test_aes_enc_blocks_in_place_invalid_length() {
+    let mut data = [0x11u8; 20];
+    assert!(aes_enc_blocks_in_place(&mut data, b"Very secret key.").is_err());
+    assert!(aes_dec_blocks_in_place(&mut data, b"Very secret key.").is_err());
+    assert_eq!(data, [0x11u8; 20]);
+}
+
+#[test]
+fn test_aes_enc_blocks_in_place_invalid_key() {
+    let mut data = [0x11u8; 32];
+    assert!(aes_enc_blocks_in_place(&mut data, [0u8; 17]).is_err());
+    assert_eq!(data, [0x11u8; 32]);
+}
+
+#[test]
+fn test_aes_enc_blocks_with_offsets_matches_manual_xex() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext: Vec<[u8; 16]> = (0..5u8).map(|i| [i.wrapping_mul(0x3b); 16]).collect();
+    let offsets: Vec<[u8; 16]> = (0..5u8)
+        .map(|i| std::array::from_fn(|j| i ^ (j as u8).wrapping_mul(0x1d)))
+        .collect();
+
+    let mut blocks = plaintext.clone();
+    aes_enc_blocks_with_offsets(&mut blocks, &key, &offsets).expect("Encryption failed");
+
+    for i in 0..plaintext.len() {
+        let mut expected: [u8; 16] = std::array::from_fn(|j| plaintext[i][j] ^ offsets[i][j]);
+        expected = aes_enc_block(&expected, &key).unwrap();
+        for j in 0..16 {
+            expected[j] ^= offsets[i][j];
+        }
+        assert_eq!(blocks[i], expected);
+    }
+}
+
+#[test]
+fn test_aes_enc_blocks_with_zero_offsets_is_ecb() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext =
+        hex_decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51").unwrap();
+
+    let mut blocks = [[0u8; 16]; 2];
+    blocks[0].copy_from_slice(&plaintext[..16]);
+    blocks[1].copy_from_slice(&plaintext[16..]);
+    aes_enc_blocks_with_offsets(&mut blocks, &key, &[[0u8; 16]; 2]).expect("Encryption failed");
+
+    assert_eq!(
+        blocks.concat(),
+        aes_enc_ecb(&plaintext, &key, None).unwrap()
+    );
+}
+
+#[test]
+fn test_aes_enc_blocks_with_offsets_invalid_input() {
+    let mut blocks = [[0x11u8; 16]; 2];
+
+    assert!(aes_enc_blocks_with_offsets(&mut blocks, b"Very secret key.", &[[0u8; 16]]).is_err());
+    assert!(aes_enc_blocks_with_offsets(&mut blocks, [0u8; 17], &[[0u8; 16]; 2]).is_err());
+    assert_eq!(blocks, [[0x11u8; 16]; 2]);
+}
+
+#[test]
+fn test_aes_enc_ecb_traced_pkcs7() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext = b"Example plaintext.";
+
+    let (ciphertext, blocks) = aes_enc_ecb_traced(plaintext, &key, Some("PKCS7")).unwrap();
+    assert_eq!(
+        ciphertext,
+        aes_enc_ecb(plaintext, &key, Some("PKCS7")).unwrap()
+    );
+
+    // 18 bytes of plaintext: one full block and a final block with 14 bytes of padding
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0], *b"Example plaintex");
+    assert_eq!(blocks[1][..2], *b"t.");
+    assert_eq!(blocks[1][2..], [14u8; 14]);
+
+    for (block, encrypted) in blocks.iter().zip(ciphertext.chunks(16)) {
+        assert_eq!(aes_enc_block(block, &key).unwrap(), encrypted);
+    }
+}
+
+#[test]
+fn test_aes_enc_ecb_traced_errors() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    assert!(aes_enc_ecb_traced(b"not aligned", &key, None).is_err());
+    assert!(aes_enc_ecb_traced([0u8; 16], &key[..15], None).is_err());
+}
+
+#[test]
+fn test_aes_enc_ecb_traced_rejects_unknown_padding() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    // Unknown padding must not truncate the trailing partial block
+    for padding in ["pkcs7", "pkcs#7", "0X80"] {
+        assert!(aes_enc_ecb_traced(b"0123456789abcdefSECRET!", &key, Some(padding)).is_err());
+        assert!(aes_enc_ecb(b"0123456789abcdefSECRET!", &key, Some(padding)).is_err());
+    }
+    assert!(aes_enc_ecb_traced(b"0123456789abcdef", &key, Some("pkcs7")).is_err());
+}
+
+#[test]
+fn test_aes_dec_ecb_rejects_unknown_padding() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let ciphertext = aes_enc_ecb(b"Example plaintext.", &key, Some("PKCS7")).unwrap();
+
+    // The padding must not be returned as part of the plaintext
+    for padding in ["pkcs7", "pkcs#7", "0X80"] {
+        assert!(aes_dec_ecb(&ciphertext, &key, Some(padding)).is_err());
+    }
+}
+
+#[test]
+fn test_blocks_equal_under_key() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let a = *b"Duplicate block.";
+    let mut b = a;
+
+    assert!(blocks_equal_under_key(&a, &b, &key).unwrap());
+
+    b[15] ^= 0x01;
+    assert!(!blocks_equal_under_key(&a, &b, &key).unwrap());
+    assert_ne!(
+        aes_enc_block(&a, &key).unwrap(),
+        aes_enc_block(&b, &key).unwrap()
+    );
+
+    assert!(blocks_equal_under_key(&a, &a, &key[..15]).is_err());
+}
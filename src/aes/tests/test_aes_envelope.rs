@@ -0,0 +1,395 @@
+use super::super::aes_cbc::*;
+use super::super::aes_cmac::*;
+use super::super::aes_envelope::*;
+use super::super::aes_kdf::*;
+use hex::decode as hex_decode;
+use zeroize::{Zeroize, Zeroizing};
+
+const IV: [u8; 16] = [
+    0xfe, 0x5b, 0xf0, 0x4a, 0x23, 0x1c, 0xa7, 0x79, 0x5a, 0xee, 0x7e, 0xc2, 0xe4, 0x3b, 0x14, 0x4a,
+];
+
+#[test]
+fn test_seal_open_round_trip() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    for len in [0, 1, 15, 16, 17, 100] {
+        let plaintext: Vec<u8> = (0..len as u8).collect();
+        let sealed = seal_with_iv(&plaintext, &key, &IV).unwrap();
+        assert_eq!(open(&sealed, &key).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn test_seal_open_accept_arrays() {
+    let key = [0x2bu8; 32];
+    let plaintext = [0x6bu8; 20];
+
+    let sealed = seal_with_iv(plaintext, key, &IV).unwrap();
+    assert_eq!(sealed, seal_with_iv(&plaintext[..], &key[..], &IV).unwrap());
+    assert_eq!(open(sealed, key).unwrap(), plaintext);
+}
+
+#[test]
+fn test_seal_random_iv() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext = b"Example plaintext.";
+
+    let first = seal(plaintext, &key).unwrap();
+    let second = seal(plaintext, &key).unwrap();
+
+    // Each envelope carries its own IV
+    assert_eq!(first.len(), 1 + 16 + 32 + 16);
+    assert_ne!(first[1..17], second[1..17]);
+    assert_ne!(first, second);
+
+    assert_eq!(open(&first, &key).unwrap(), plaintext);
+    assert_eq!(open(&second, &key).unwrap(), plaintext);
+    assert!(seal(plaintext, &key[..15]).is_err());
+}
+
+#[test]
+fn test_seal_layout() {
+    let key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let plaintext = b"Example plaintext.";
+    let sealed = seal_with_iv(plaintext, &key, &IV).unwrap();
+
+    let (enc_key, mac_key) = derive_enc_mac_keys(&key, 32).unwrap();
+    let ciphertext = aes_enc_cbc(plaintext, &enc_key, &IV, Some("PKCS7")).unwrap();
+
+    let mut mac_input = vec![ENVELOPE_VERSION];
+    mac_input.extend_from_slice(&IV);
+    mac_input.extend_from_slice(&ciphertext);
+    let tag = aes_cmac(&mac_input, &mac_key).unwrap();
+
+    assert_eq!(sealed.len(), 1 + 16 + 32 + 16);
+    assert_eq!(sealed[0], ENVELOPE_VERSION);
+    assert_eq!(sealed[1..17], IV);
+    assert_eq!(sealed[17..49], ciphertext[..]);
+    assert_eq!(sealed[49..], tag);
+}
+
+#[test]
+fn test_open_rejects_tampering() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let sealed = seal_with_iv(b"Example plaintext.", &key, &IV).unwrap();
+
+    // Flipping any single byte after the version must fail authentication
+    for i in 1..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 0x01;
+        assert!(
+            open(&tampered, &key).is_err(),
+            "byte {} not authenticated",
+            i
+        );
+    }
+}
+
+#[test]
+fn test_open_rejects_unknown_version() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let mut sealed = seal_with_iv(b"Example plaintext.", &key, &IV).unwrap();
+    sealed[0] = 0x02;
+
+    let result = open(&sealed, &key);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "AES ENVELOPE ERROR: Unsupported version 0x02"
+        );
+    }
+}
+
+#[test]
+fn test_open_rejects_wrong_key() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let wrong_key = hex_decode("2b7e151628aed2a6abf7158809cf4f3d").unwrap();
+    let sealed = seal_with_iv(b"Example plaintext.", &key, &IV).unwrap();
+
+    assert!(open(&sealed, &wrong_key).is_err());
+}
+
+#[test]
+fn test_open_rejects_short_or_truncated_input() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let sealed = seal_with_iv(b"Example plaintext.", &key, &IV).unwrap();
+
+    assert!(open([], &key).is_err());
+    assert!(open(&sealed[..48], &key).is_err());
+    assert!(open(&sealed[..sealed.len() - 1], &key).is_err());
+}
+
+#[test]
+fn test_cbc_cmac_round_trip() {
+    let enc_key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let mac_key =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let cipher = CbcCmac::new(&enc_key, &mac_key).unwrap();
+
+    for (aad, plaintext) in [(&b""[..], &b""[..]), (b"header", b"Example plaintext.")] {
+        let sealed = cipher.seal(&IV, aad, plaintext).unwrap();
+        assert_eq!(cipher.open(&IV, aad, &sealed).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn test_cbc_cmac_matches_manual_construction() {
+    let enc_key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let mac_key = hex_decode("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").unwrap();
+    let aad = b"associated data";
+    let plaintext = b"Example plaintext.";
+
+    let cipher = CbcCmac::new(&enc_key, &mac_key).unwrap();
+    let sealed = cipher.seal(&IV, aad, plaintext).unwrap();
+
+    // Build the expected output from the individual primitives
+    let ciphertext = aes_enc_cbc(plaintext, &enc_key, &IV, Some("PKCS7")).unwrap();
+    let mut mac_input = (aad.len() as u64).to_be_bytes().to_vec();
+    mac_input.extend_from_slice(aad);
+    mac_input.extend_from_slice(&IV);
+    mac_input.extend_from_slice(&ciphertext);
+    let tag = aes_cmac(&mac_input, &mac_key).unwrap();
+
+    let mut expected = ciphertext;
+    expected.extend_from_slice(&tag);
+    assert_eq!(sealed, expected);
+}
+
+#[test]
+fn test_cbc_cmac_open_returns_no_plaintext_on_failure() {
+    let enc_key = [0x11; 16];
+    let mac_key = [0x22; 16];
+    let cipher = CbcCmac::new(enc_key, mac_key).unwrap();
+    let aad = b"header";
+
+    // A tampered tag is rejected before anything is decrypted
+    let mut sealed = cipher.seal(&IV, aad, b"Example plaintext.").unwrap();
+    let last = sealed.len() - 1;
+    sealed[last] ^= 0x01;
+    let result = cipher.open(&IV, aad, &sealed);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "AES ENVELOPE ERROR: Authentication failed"
+    );
+
+    // A valid tag over a ciphertext without valid padding still yields no
+    // plaintext
+    let ciphertext = aes_enc_cbc([0x42u8; 32], enc_key, &IV, None).unwrap();
+    let mut mac_input = (aad.len() as u64).to_be_bytes().to_vec();
+    mac_input.extend_from_slice(aad);
+    mac_input.extend_from_slice(&IV);
+    mac_input.extend_from_slice(&ciphertext);
+    let mut forged = ciphertext.clone();
+    forged.extend_from_slice(&aes_cmac(&mac_input, mac_key).unwrap());
+
+    assert!(cipher.open(&IV, aad, &forged).is_err());
+}
+
+#[test]
+fn test_cbc_cmac_open_rejects_truncated_tag() {
+    let cipher = CbcCmac::new([0x11; 16], [0x22; 16]).unwrap();
+    let sealed = cipher.seal(&IV, b"header", b"Example plaintext.").unwrap();
+
+    assert!(cipher
+        .open(&IV, b"header", &sealed[..sealed.len() - 1])
+        .is_err());
+    assert!(cipher
+        .open(&IV, b"header", &sealed[..sealed.len() - 16])
+        .is_err());
+    assert!(cipher.open(&IV, b"header", &sealed[..16]).is_err());
+}
+
+#[test]
+fn test_cbc_cmac_open_rejects_modified_aad() {
+    let cipher = CbcCmac::new([0x11; 16], [0x22; 16]).unwrap();
+    let aad = b"header".to_vec();
+    let sealed = cipher.seal(&IV, &aad, b"Example plaintext.").unwrap();
+
+    for i in 0..aad.len() {
+        let mut tampered = aad.clone();
+        tampered[i] ^= 0x01;
+        assert!(cipher.open(&IV, &tampered, &sealed).is_err());
+    }
+}
+
+#[test]
+fn test_cbc_cmac_open_rejects_different_aad() {
+    let cipher = CbcCmac::new([0x11; 16], [0x22; 16]).unwrap();
+    let plaintext = b"Example plaintext.";
+
+    let sealed = cipher.seal(&IV, b"header", plaintext).unwrap();
+    for aad in [&b""[..], b"heade", b"header\0", b"other!"] {
+        assert!(cipher.open(&IV, aad, &sealed).is_err());
+    }
+
+    // Data sealed without associated data is bound to the empty AAD
+    let sealed = cipher.seal(&IV, b"", plaintext).unwrap();
+    assert!(cipher.open(&IV, b"\0", &sealed).is_err());
+    assert_eq!(cipher.open(&IV, b"", &sealed).unwrap(), plaintext);
+}
+
+#[test]
+fn test_cbc_cmac_open_rejects_modified_ciphertext() {
+    let cipher = CbcCmac::new([0x11; 16], [0x22; 16]).unwrap();
+    let mut sealed = cipher.seal(&IV, b"header", b"Example plaintext.").unwrap();
+    sealed[0] ^= 0x80;
+
+    assert!(cipher.open(&IV, b"header", &sealed).is_err());
+}
+
+#[test]
+fn test_cbc_cmac_new_rejects_invalid_keys() {
+    assert!(CbcCmac::new([0u8; 15], [0u8; 16]).is_err());
+    assert!(CbcCmac::new([0u8; 16], [0u8; 31]).is_err());
+}
+
+#[test]
+fn test_cbc_cmac_open_rejects_modified_iv() {
+    let enc_key = [0x11; 16];
+    let cipher = CbcCmac::new(enc_key, [0x22; 16]).unwrap();
+    let plaintext = b"Example plaintext.";
+    let sealed = cipher.seal(&IV, b"header", plaintext).unwrap();
+
+    // In plain CBC, a flipped IV bit silently flips the same plaintext bit
+    let mut tampered_iv = IV;
+    tampered_iv[0] ^= 0x01;
+    let ciphertext = &sealed[..sealed.len() - 16];
+    let decrypted = aes_dec_cbc(ciphertext, enc_key, &tampered_iv, Some("PKCS7")).unwrap();
+    assert_eq!(decrypted[0], plaintext[0] ^ 0x01);
+
+    // The IV is authenticated, so every single bit flip is rejected
+    for bit in 0..128 {
+        let mut tampered_iv = IV;
+        tampered_iv[bit / 8] ^= 1 << (bit % 8);
+        assert!(cipher.open(&tampered_iv, b"header", &sealed).is_err());
+    }
+}
+
+#[test]
+fn test_derive_envelope_subkeys_are_zeroizing() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    let subkeys: Zeroizing<(Vec<u8>, Vec<u8>)> = derive_envelope_subkeys(&key).unwrap();
+
+    assert_eq!(*subkeys, derive_enc_mac_keys(&key, 16).unwrap());
+    assert!(derive_envelope_subkeys(&key[..15]).is_err());
+}
+
+#[test]
+fn test_cmac_zeroize_clears_key_material() {
+    let mut first = Cmac::new([0x11u8; 16]).unwrap();
+    let mut second = Cmac::new([0x22u8; 16]).unwrap();
+    assert_ne!(first.mac(b"message"), second.mac(b"message"));
+
+    first.zeroize();
+    second.zeroize();
+
+    // Nothing of either key is left, so both compute the same function
+    assert_eq!(first.mac(b"message"), second.mac(b"message"));
+}
+
+#[test]
+fn test_derive_envelope_subkeys_match_master_key_length() {
+    for key_len in [16, 24, 32] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+        let subkeys = derive_envelope_subkeys(&key).unwrap();
+
+        assert_eq!(subkeys.0.len(), key_len);
+        assert_eq!(subkeys.1.len(), key_len);
+        assert_eq!(*subkeys, derive_enc_mac_keys(&key, key_len).unwrap());
+    }
+}
+
+#[test]
+fn test_seal_detailed_matches_seal() {
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext = b"Example plaintext.";
+    let detailed = seal_detailed(plaintext, &key, &IV).unwrap();
+
+    assert_eq!(detailed.iv_or_nonce, IV);
+    assert_eq!(detailed.aad_len, 0);
+
+    // The parts reconstruct the envelope for open
+    let mut sealed = vec![ENVELOPE_VERSION];
+    sealed.extend_from_slice(&detailed.iv_or_nonce);
+    sealed.extend_from_slice(&detailed.ciphertext);
+    sealed.extend_from_slice(&detailed.tag);
+    assert_eq!(sealed, seal_with_iv(plaintext, &key, &IV).unwrap());
+    assert_eq!(open(&sealed, &key).unwrap(), plaintext);
+}
+
+#[test]
+fn test_cbc_cmac_seal_detailed_matches_seal() {
+    let cipher = CbcCmac::new([0x11; 16], [0x22; 16]).unwrap();
+    let aad = b"associated data";
+    let plaintext = b"Example plaintext.";
+    let detailed = cipher.seal_detailed(&IV, aad, plaintext).unwrap();
+
+    assert_eq!(detailed.iv_or_nonce, IV);
+    assert_eq!(detailed.aad_len, aad.len());
+
+    let mut sealed = detailed.ciphertext.clone();
+    sealed.extend_from_slice(&detailed.tag);
+    assert_eq!(sealed, cipher.seal(&IV, aad, plaintext).unwrap());
+    assert_eq!(
+        cipher.open(&detailed.iv_or_nonce, aad, &sealed).unwrap(),
+        plaintext
+    );
+}
+
+#[test]
+fn test_cmac_then_cbc_round_trip() {
+    let enc_key = [0x11; 16];
+    let mac_key = [0x22; 32];
+
+    for len in [0, 1, 15, 16, 17, 100] {
+        let plaintext = vec![0x42u8; len];
+        let ciphertext = cmac_then_cbc_encrypt(&plaintext, enc_key, mac_key, &IV).unwrap();
+
+        // The tag is encrypted along with the plaintext
+        assert_eq!(ciphertext.len(), (len + 16) / 16 * 16 + 16);
+        assert_eq!(
+            cmac_then_cbc_decrypt(&ciphertext, enc_key, mac_key, &IV).unwrap(),
+            plaintext
+        );
+    }
+}
+
+#[test]
+fn test_cmac_then_cbc_matches_manual_construction() {
+    let enc_key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let mac_key = hex_decode("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").unwrap();
+    let plaintext = b"Example plaintext.";
+
+    let mut tagged = plaintext.to_vec();
+    tagged.extend_from_slice(&aes_cmac(plaintext, &mac_key).unwrap());
+
+    assert_eq!(
+        cmac_then_cbc_encrypt(plaintext, &enc_key, &mac_key, &IV).unwrap(),
+        aes_enc_cbc(&tagged, &enc_key, &IV, Some("PKCS7")).unwrap()
+    );
+}
+
+#[test]
+fn test_cmac_then_cbc_decrypt_rejects_tampering() {
+    let enc_key = [0x11; 16];
+    let mac_key = [0x22; 16];
+    let ciphertext = cmac_then_cbc_encrypt(b"Example plaintext.", enc_key, mac_key, &IV).unwrap();
+
+    for i in 0..ciphertext.len() {
+        let mut tampered = ciphertext.clone();
+        tampered[i] ^= 0x01;
+        let result = cmac_then_cbc_decrypt(&tampered, enc_key, mac_key, &IV);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "AES MAC-THEN-ENCRYPT ERROR: Decryption failed"
+        );
+    }
+
+    assert!(cmac_then_cbc_decrypt(&ciphertext, enc_key, [0x23; 16], &IV).is_err());
+    assert!(cmac_then_cbc_decrypt(&ciphertext[..16], enc_key, mac_key, &IV).is_err());
+    assert!(cmac_then_cbc_decrypt(&ciphertext, &enc_key[..15], mac_key, &IV).is_err());
+}
@@ -0,0 +1,89 @@
+use super::super::aes_cmac::*;
+use super::super::aes_kdf::*;
+use hex::decode as hex_decode;
+
+#[test]
+fn test_derive_subkeys_matches_cmac_of_labels() {
+    let master = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let (enc_key, mac_key) = derive_subkeys(&master).unwrap();
+
+    assert_eq!(enc_key, aes_cmac(b"enc", &master).unwrap());
+    assert_eq!(mac_key, aes_cmac(b"mac", &master).unwrap());
+}
+
+#[test]
+fn test_derive_subkeys_keys_differ() {
+    let master =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+    let (enc_key, mac_key) = derive_subkeys(&master).unwrap();
+
+    assert_ne!(enc_key, mac_key);
+}
+
+#[test]
+fn test_derive_subkeys_deterministic() {
+    let master = hex_decode("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").unwrap();
+
+    assert_eq!(
+        derive_subkeys(&master).unwrap(),
+        derive_subkeys(&master).unwrap()
+    );
+}
+
+#[test]
+fn test_derive_subkeys_different_masters() {
+    let master1 = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let master2 = hex_decode("2b7e151628aed2a6abf7158809cf4f3d").unwrap();
+
+    assert_ne!(
+        derive_subkeys(&master1).unwrap(),
+        derive_subkeys(&master2).unwrap()
+    );
+}
+
+#[test]
+fn test_derive_subkeys_invalid_master_length() {
+    assert!(derive_subkeys([0u8; 15]).is_err());
+}
+
+#[test]
+fn test_derive_enc_mac_keys_known_answer() {
+    // Independently computed SP 800-108 counter mode output for AES-192 keys
+    let master = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let (enc_key, mac_key) = derive_enc_mac_keys(&master, 24).unwrap();
+
+    assert_eq!(
+        enc_key,
+        hex_decode("80b7afcd613497a4d8f8d9da08b6290627853d2a7ed00fbf").unwrap()
+    );
+    assert_eq!(
+        mac_key,
+        hex_decode("b7e364a89c90caeb814d4d941f56071ccb464e94cec2d767").unwrap()
+    );
+}
+
+#[test]
+fn test_derive_enc_mac_keys_differ_and_deterministic() {
+    let master =
+        hex_decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap();
+
+    for key_size in [16, 24, 32] {
+        let (enc_key, mac_key) = derive_enc_mac_keys(&master, key_size).unwrap();
+
+        assert_eq!(enc_key.len(), key_size);
+        assert_eq!(mac_key.len(), key_size);
+        assert_ne!(enc_key, mac_key);
+        assert_eq!(
+            derive_enc_mac_keys(&master, key_size).unwrap(),
+            (enc_key, mac_key)
+        );
+    }
+}
+
+#[test]
+fn test_derive_enc_mac_keys_invalid_sizes() {
+    let master = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+
+    assert!(derive_enc_mac_keys(&master, 20).is_err());
+    assert!(derive_enc_mac_keys([0u8; 15], 16).is_err());
+}
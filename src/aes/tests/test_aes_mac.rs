@@ -0,0 +1,103 @@
+use super::super::aes_cmac::*;
+use super::super::aes_mac::*;
+use super::test_aes_cmac::check_mac;
+use hex::decode as hex_decode;
+
+#[test]
+fn test_cbc_mac() {
+    // Reference values computed as the last block of AES-CBC with a zero IV
+    // over the message padded with 0x80 and zeros.
+    let key = hex_decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let cbc_mac = CbcMac::new(&key).unwrap();
+
+    let cases = [
+        (0, "f6c71eedc3d99bb183cb5b8d1568e606"),
+        (15, "e9089f2b08858dda9b2dc97bbb5db81b"),
+        (16, "fa8f18686779bb552dd9215ced652d47"),
+        (40, "27b9d769f792e00f2ccdc9dfd4f24ea3"),
+    ];
+
+    for (len, expected) in cases {
+        let message: Vec<u8> = (0..len as u8).collect();
+        assert_eq!(
+            cbc_mac.mac(&message).to_vec(),
+            hex_decode(expected).unwrap(),
+            "length {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn test_pmac_aes_128() {
+    // PMAC-AES-128 test vectors of the PMAC reference implementation
+    let key = hex_decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let pmac = Pmac::new(&key).unwrap();
+
+    let cases = [
+        (0, "4399572cd6ea5341b8d35876a7098af7"),
+        (3, "256ba5193c1b991b4df0c51f388a9e27"),
+        (16, "ebbd822fa458daf6dfdad7c27da76338"),
+        (20, "0412ca150bbf79058d8c75a58c993f55"),
+        (32, "e97ac04e9e5e3399ce5355cd7407bc75"),
+        (34, "5cba7d5eb24f7c86ccc54604e53d5512"),
+    ];
+
+    for (len, expected) in cases {
+        let message: Vec<u8> = (0..len as u8).collect();
+        assert_eq!(
+            pmac.mac(&message).to_vec(),
+            hex_decode(expected).unwrap(),
+            "length {}",
+            len
+        );
+    }
+
+    assert_eq!(
+        pmac.mac([0u8; 1000]).to_vec(),
+        hex_decode("c2c9fa1d9985f6f0d2aff915a0e8d910").unwrap()
+    );
+}
+
+#[test]
+fn test_mac_trait_all_implementors() {
+    for key_len in [16, 24, 32] {
+        let key: Vec<u8> = (0..key_len as u8).collect();
+        let macs: [Box<dyn Mac>; 3] = [
+            Box::new(Cmac::new(&key).unwrap()),
+            Box::new(CbcMac::new(&key).unwrap()),
+            Box::new(Pmac::new(&key).unwrap()),
+        ];
+
+        for mac in &macs {
+            check_mac(mac.as_ref());
+        }
+
+        // The algorithms produce different tags for the same key and message.
+        // CBC-MAC and PMAC coincide for a single padded block, so the message
+        // spans several blocks.
+        let message = [0x5au8; 40];
+        let tags: Vec<[u8; 16]> = macs.iter().map(|mac| mac.mac(&message)).collect();
+        assert_ne!(tags[0], tags[1]);
+        assert_ne!(tags[0], tags[2]);
+        assert_ne!(tags[1], tags[2]);
+    }
+}
+
+#[test]
+fn test_mac_invalid_key_and_debug() {
+    assert!(CbcMac::new([0u8; 15]).is_err());
+    assert!(Pmac::new([0u8; 15]).is_err());
+
+    let cbc_mac = CbcMac::new([0x2bu8; 16]).unwrap();
+    assert_eq!(
+        format!("{:?}", cbc_mac),
+        r#"CbcMac { expanded_key: "<redacted>", nr: 10 }"#
+    );
+
+    let pmac = Pmac::new([0x2bu8; 32]).unwrap();
+    assert_eq!(
+        format!("{:?}", pmac),
+        r#"Pmac { expanded_key: "<redacted>", nr: 14, l: "<redacted>" }"#
+    );
+}
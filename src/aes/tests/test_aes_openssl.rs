@@ -0,0 +1,103 @@
+use super::super::aes_openssl::*;
+use hex::decode as hex_decode;
+
+// Created with
+// printf 'Example plaintext from the openssl CLI.\n' | openssl enc -<cipher> -md md5 -pass pass:password
+const PLAINTEXT: &[u8] = b"Example plaintext from the openssl CLI.\n";
+
+#[test]
+fn test_decrypt_openssl_cli_output() {
+    let files = [
+        (
+            "aes-128-cbc",
+            "53616c7465645f5f76e2a0dfa806f463c5acc0e41acf405dd1db94a78451522bbd58113b7f3df3cde904057d796d9d95a0ca89a52a084bf35fa3bf18c6ac881b",
+        ),
+        (
+            "aes-192-cbc",
+            "53616c7465645f5f33dc58f08b7894e1eb3c900b1ad4756bd6cf94e5859246bd1ba48d6061e88460408a434aa5c8bad623aec4ff0487f4ce43a1559d42f649ce",
+        ),
+        (
+            "aes-256-cbc",
+            "53616c7465645f5fc70f1ff788ec4f9212017e6d8001ca8e77a3c3a2c34623cd82981aff5a1370756a8cbb089f19b480a8644857d3a8be13b2462c5b360cd8c4",
+        ),
+    ];
+
+    for (cipher, data) in files {
+        let data = hex_decode(data).unwrap();
+        assert_eq!(
+            decrypt_openssl(&data, b"password", cipher).unwrap(),
+            PLAINTEXT
+        );
+    }
+}
+
+#[test]
+fn test_decrypt_openssl_empty_plaintext() {
+    // printf '' | openssl enc -aes-256-cbc -md md5 -pass pass:password
+    let data =
+        hex_decode("53616c7465645f5f86377a882eff168265a1fae869078ac2cd6b966a9b55775c").unwrap();
+
+    assert!(decrypt_openssl(&data, b"password", "aes-256-cbc")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_decrypt_openssl_rejects_missing_header() {
+    let data =
+        hex_decode("53616c7465645f5f86377a882eff168265a1fae869078ac2cd6b966a9b55775c").unwrap();
+
+    // Header-less output, as written by `openssl enc -nosalt`
+    let result = decrypt_openssl(&data[16..], b"password", "aes-256-cbc");
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "OPENSSL COMPAT ERROR: Missing Salted__ header"
+        );
+    }
+
+    assert!(decrypt_openssl(OPENSSL_SALT_MAGIC, b"password", "aes-256-cbc").is_err());
+}
+
+#[test]
+fn test_decrypt_openssl_rejects_unsupported_cipher() {
+    let data =
+        hex_decode("53616c7465645f5f86377a882eff168265a1fae869078ac2cd6b966a9b55775c").unwrap();
+
+    assert!(decrypt_openssl(&data, b"password", "aes-256-ecb").is_err());
+}
+
+#[test]
+fn test_encrypt_openssl_with_salt_reproduces_cli_output() {
+    let expected = hex_decode("53616c7465645f5f76e2a0dfa806f463c5acc0e41acf405dd1db94a78451522bbd58113b7f3df3cde904057d796d9d95a0ca89a52a084bf35fa3bf18c6ac881b").unwrap();
+    let salt: [u8; 8] = expected[8..16].try_into().unwrap();
+
+    assert_eq!(
+        encrypt_openssl_with_salt(PLAINTEXT, b"password", "aes-128-cbc", &salt).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_encrypt_openssl_round_trip() {
+    for cipher in ["aes-128-cbc", "aes-192-cbc", "aes-256-cbc"] {
+        let first = encrypt_openssl(PLAINTEXT, b"password", cipher).unwrap();
+        let second = encrypt_openssl(PLAINTEXT, b"password", cipher).unwrap();
+
+        assert!(first.starts_with(OPENSSL_SALT_MAGIC));
+        // A fresh salt is drawn for every call
+        assert_ne!(first[8..16], second[8..16]);
+
+        assert_eq!(
+            decrypt_openssl(&first, b"password", cipher).unwrap(),
+            PLAINTEXT
+        );
+        assert_eq!(
+            decrypt_openssl(&second, b"password", cipher).unwrap(),
+            PLAINTEXT
+        );
+    }
+
+    assert!(encrypt_openssl(PLAINTEXT, b"password", "aes-256-gcm").is_err());
+}
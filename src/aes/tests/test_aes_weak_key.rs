@@ -0,0 +1,98 @@
+use super::super::aes_weak_key::*;
+use hex::decode as hex_decode;
+
+#[test]
+fn test_weak_key_all_same_byte() {
+    assert!(weak_key_warning(&[0x00; 16]).is_some());
+    assert!(weak_key_warning(&[0xFF; 24]).is_some());
+    assert!(weak_key_warning(&[0x41; 32]).is_some());
+}
+
+#[test]
+fn test_weak_key_known_test_vectors() {
+    let keys = [
+        "2b7e151628aed2a6abf7158809cf4f3c",
+        "000102030405060708090a0b0c0d0e0f",
+        "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b",
+        "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+    ];
+
+    for key in keys {
+        assert!(weak_key_warning(&hex_decode(key).unwrap()).is_some());
+    }
+}
+
+#[test]
+fn test_weak_key_regular_key_passes() {
+    let key = hex_decode("c6a13b37878f5b826f4f8162a1c8d879").unwrap();
+    assert!(weak_key_warning(&key).is_none());
+}
+
+#[test]
+fn test_weak_key_does_not_change_result() {
+    use super::super::aes_core::aes_enc_block;
+
+    // A weak key only triggers a warning, encryption still succeeds
+    let ciphertext = aes_enc_block(&[0u8; 16], &[0u8; 16]).unwrap();
+    assert_eq!(
+        ciphertext.to_vec(),
+        hex_decode("66e94bd4ef8a2c3b884cfa59ca342b2e").unwrap()
+    );
+}
+
+/// Run `f` and return the number of weak keys it reported on this thread.
+fn weak_keys_detected(f: impl FnOnce()) -> usize {
+    let before = WEAK_KEYS_DETECTED.with(|count| count.get());
+    f();
+    WEAK_KEYS_DETECTED.with(|count| count.get()) - before
+}
+
+#[test]
+fn test_weak_key_caller_key_is_reported() {
+    use super::super::aes_core::aes_enc_block;
+
+    assert_eq!(
+        weak_keys_detected(|| {
+            aes_enc_block(&[0u8; 16], &[0u8; 16]).unwrap();
+        }),
+        1
+    );
+}
+
+#[cfg(feature = "rng")]
+#[test]
+fn test_weak_key_ctr_drbg_new_is_not_reported() {
+    use crate::rng::CtrDrbg;
+
+    // CTR_DRBG instantiation always starts from the all-zero key
+    let entropy = hex_decode(
+        "e4bc23c5089a19d86f4119cb3fa08c0a4991e0a1def17e101e4c14d9c323460a\
+         7c2fb58e0b086c6c57b55f56cae25bad",
+    )
+    .unwrap();
+
+    assert_eq!(
+        weak_keys_detected(|| {
+            let mut drbg = CtrDrbg::new(&entropy, &[], &[]).unwrap();
+            drbg.generate(&mut [0u8; 32]).unwrap();
+        }),
+        0
+    );
+}
+
+#[cfg(feature = "cmac-prf")]
+#[test]
+fn test_weak_key_cmac_prf_folding_is_not_reported() {
+    use super::super::aes_cmac_prf::{aes_cmac_prf_128, aes_cmac_prf_256};
+
+    // Keys of other lengths are folded with the all-zero key
+    let key = hex_decode("c6a13b37878f5b826f4f8162a1c8d879c6a1").unwrap();
+
+    assert_eq!(
+        weak_keys_detected(|| {
+            aes_cmac_prf_128(&key, b"message").unwrap();
+            aes_cmac_prf_256(&key, b"message").unwrap();
+        }),
+        0
+    );
+}
@@ -0,0 +1,126 @@
+//! Length-Prefixed Framing
+//!
+//! This module provides helpers to package several messages, e.g. multiple
+//! ciphertexts, into one blob and to split such a blob back into the
+//! individual messages. Each message is prefixed with its length as a 4-byte
+//! big-endian integer:
+//!
+//! ```text
+//! len(m0) (4) || m0 || len(m1) (4) || m1 || ...
+//! ```
+//!
+//! # Features
+//!
+//! - `frame`: Concatenates messages with a length prefix for each. Messages
+//!   longer than `u32::MAX` bytes result in an error.
+//!
+//! - `unframe`: Parses a framed blob back into the messages. Truncated or
+//!   inconsistent input results in an error.
+//!
+//! # Example
+//!
+//! ```
+//! use soft_aes::framing::{frame, unframe};
+//!
+//! let messages = vec![b"first".to_vec(), Vec::new(), b"third".to_vec()];
+//!
+//! let framed = frame(&messages).expect("Framing failed");
+//! let unframed = unframe(&framed).expect("Unframing failed");
+//!
+//! assert_eq!(unframed, messages);
+//! ```
+//!
+//! # Disclaimer
+//!
+//! - The framing is not authenticated. Reordering, dropping or duplicating
+//!   whole frames is not detected, unless the messages themselves are
+//!   authenticated together, e.g. by a MAC over the framed blob.
+
+use std::error::Error;
+
+/// Length of the big-endian length prefix of each frame in bytes.
+pub const FRAME_LEN_PREFIX_SIZE: usize = 4;
+
+/// Concatenate messages, prefixing each with its 4-byte big-endian length.
+///
+/// # Arguments
+///
+/// * `messages` - The messages to frame.
+///
+/// # Returns
+///
+/// A `Result` containing the framed blob
+/// `len(m0) || m0 || len(m1) || m1 || ...`, or an error if a message is
+/// longer than `u32::MAX` bytes.
+pub fn frame(messages: &[Vec<u8>]) -> Result<Vec<u8>, Box<dyn Error>> {
+    // Check every length before anything is copied
+    let mut total_len: usize = 0;
+    for (index, message) in messages.iter().enumerate() {
+        if u32::try_from(message.len()).is_err() {
+            return Err(format!(
+                "FRAMING ERROR: Message {} is longer than u32::MAX bytes, got {} bytes",
+                index,
+                message.len()
+            )
+            .into());
+        }
+        total_len = total_len
+            .checked_add(FRAME_LEN_PREFIX_SIZE + message.len())
+            .ok_or("FRAMING ERROR: Framed data is too large")?;
+    }
+
+    let mut framed = Vec::with_capacity(total_len);
+    for message in messages {
+        framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        framed.extend_from_slice(message);
+    }
+
+    Ok(framed)
+}
+
+/// Parse a blob created with `frame` back into the individual messages.
+///
+/// # Arguments
+///
+/// * `data` - The framed blob.
+///
+/// # Returns
+///
+/// A `Result` containing the messages, or an error if a length prefix is
+/// truncated or a length exceeds the remaining data.
+pub fn unframe(data: &[u8]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+    let mut messages = Vec::new();
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let offset = data.len() - rest.len();
+
+        if rest.len() < FRAME_LEN_PREFIX_SIZE {
+            return Err(format!(
+                "FRAMING ERROR: Truncated length prefix at offset {}, got {} bytes",
+                offset,
+                rest.len()
+            )
+            .into());
+        }
+
+        let (prefix, body) = rest.split_at(FRAME_LEN_PREFIX_SIZE);
+        let len = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+
+        if body.len() < len {
+            return Err(format!(
+                "FRAMING ERROR: Frame at offset {} declares {} bytes, but only {} bytes remain",
+                offset,
+                len,
+                body.len()
+            )
+            .into());
+        }
+
+        let (message, remaining) = body.split_at(len);
+        messages.push(message.to_vec());
+        rest = remaining;
+    }
+
+    Ok(messages)
+}
@@ -0,0 +1,6 @@
+mod length_prefix;
+
+pub use length_prefix::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,67 @@
+use super::super::length_prefix::*;
+
+#[test]
+fn test_frame_layout() {
+    let messages = vec![vec![0xaa, 0xbb], Vec::new(), vec![0xcc]];
+
+    assert_eq!(
+        frame(&messages).unwrap(),
+        vec![0, 0, 0, 2, 0xaa, 0xbb, 0, 0, 0, 0, 0, 0, 0, 1, 0xcc]
+    );
+}
+
+#[test]
+fn test_frame_unframe_round_trip() {
+    let cases = vec![
+        Vec::new(),
+        vec![Vec::new()],
+        vec![b"single".to_vec()],
+        vec![vec![0u8; 300], Vec::new(), (0..=255).collect()],
+    ];
+
+    for messages in cases {
+        assert_eq!(unframe(&frame(&messages).unwrap()).unwrap(), messages);
+    }
+}
+
+#[test]
+fn test_unframe_truncated_prefix() {
+    let framed = frame(&[b"abc".to_vec()]).unwrap();
+    let mut data = framed.clone();
+    data.extend_from_slice(&[0, 0]);
+
+    let result = unframe(&data);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "FRAMING ERROR: Truncated length prefix at offset 7, got 2 bytes"
+        );
+    }
+}
+
+#[test]
+fn test_unframe_truncated_body() {
+    let framed = frame(&[b"first".to_vec(), b"second".to_vec()]).unwrap();
+
+    let result = unframe(&framed[..framed.len() - 1]);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(
+            e.to_string(),
+            "FRAMING ERROR: Frame at offset 9 declares 6 bytes, but only 5 bytes remain"
+        );
+    }
+
+    // Every strict prefix that cuts into a frame must fail without panicking
+    for len in 1..framed.len() {
+        if len != 9 {
+            assert!(unframe(&framed[..len]).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_unframe_oversized_length() {
+    assert!(unframe(&[0xff, 0xff, 0xff, 0xff, 0x00]).is_err());
+}
@@ -1,2 +1,7 @@
 pub mod aes;
+#[cfg(feature = "framing")]
+pub mod framing;
+#[cfg(feature = "padding")]
 pub mod padding;
+#[cfg(feature = "rng")]
+pub mod rng;
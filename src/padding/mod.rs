@@ -4,5 +4,18 @@ mod pkcs7;
 pub use padding_80::*;
 pub use pkcs7::*;
 
+/// Length of `len` bytes of data after padding to a multiple of `block_size`.
+///
+/// Both padding schemes add between 1 and `block_size` bytes. Returns `None`
+/// if the padded length would exceed the maximum size of a `Vec`
+/// (`isize::MAX` bytes), so that callers can report an error instead of
+/// overflowing or aborting on allocation.
+pub(crate) fn padded_len(len: usize, block_size: usize) -> Option<usize> {
+    (len / block_size)
+        .checked_add(1)?
+        .checked_mul(block_size)
+        .filter(|&padded| padded <= isize::MAX as usize)
+}
+
 #[cfg(test)]
 mod tests;
@@ -19,6 +19,12 @@
 //! - `unpad_80`: Removes 0x80 padding from a byte array, reverting it to its original
 //!   unpadded state.
 //!
+//! - `is_valid_80_padding`: Checks whether the final block of a byte array holds valid 0x80
+//!   padding, without modifying it.
+//!
+//! - `unpad_80_ct`: Removes 0x80 padding from the final block without branching on the data
+//!   (requires the `constant-time` feature).
+//!
 //! # Usage
 //!
 //! This padding scheme is particularly useful in cryptographic applications where
@@ -67,22 +73,31 @@ use std::error::Error;
 /// # Arguments
 ///
 /// * `data` : A mutable reference to the byte array (`Vec<u8>`) to be padded.
-/// * `block_size` : The block size (`usize`) for padding.
+/// * `block_size` : The block size (`usize`) for padding. Must be greater than
+///   0 and less than 256.
 ///
 /// # Returns
 ///
 /// * `Ok(())` if the padding is successfully applied.
-/// * `Err(Box<dyn Error>)` if the block size is invalid.
+/// * `Err(Box<dyn Error>)` if the block size is invalid (0 or >= 256), or the
+///   padded data would be too large to allocate.
 pub fn pad_80(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Error>> {
-    if block_size == 0 {
-        return Err("0x80 PADDING ERROR: Block size must be greater than 0".into());
+    if block_size == 0 || block_size >= 256 {
+        return Err(
+            "0x80 PADDING ERROR: Block size must be greater than 0 and less than 256".into(),
+        );
     }
 
-    data.push(0x80);
+    let padded_len = super::padded_len(data.len(), block_size).ok_or_else(|| {
+        format!(
+            "0x80 PADDING ERROR: Input too large, got {} bytes",
+            data.len()
+        )
+    })?;
+    data.try_reserve_exact(padded_len - data.len())?;
 
-    while data.len() % block_size != 0 {
-        data.push(0x00);
-    }
+    data.push(0x80);
+    data.resize(padded_len, 0x00);
 
     Ok(())
 }
@@ -92,24 +107,142 @@ pub fn pad_80(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Error
 /// This function removes the 0x80 padding from the provided byte array.
 /// It checks for the presence of 0x80 followed by 0x00 bytes and removes them.
 ///
+/// The padding is located by scanning backwards from the end: trailing 0x00
+/// bytes are skipped and the first non-zero byte must be 0x80. This also
+/// covers block aligned input, where `pad_80` appends a full block of
+/// padding (`0x80 0x00 ... 0x00`) that is removed entirely. Payload bytes
+/// equal to 0x80 or 0x00 in front of the padding are preserved.
+///
 /// # Arguments
 ///
 /// * `data` : A mutable reference to the byte array (`Vec<u8>`) from which
-///            padding is to be removed.
+///   padding is to be removed.
 ///
 /// # Returns
 ///
 /// * `Ok(())` if the unpadding is successfully performed.
 /// * `Err(Box<dyn Error>)` if there's an issue with the padding.
 pub fn unpad_80(data: &mut Vec<u8>) -> Result<(), Box<dyn Error>> {
-    if let Some(position) = data.iter().rposition(|&x| x == 0x80) {
-        if data[position + 1..].iter().all(|&x| x == 0x00) {
+    // The last non-zero byte marks the start of the padding
+    match data.iter().rposition(|&x| x != 0x00) {
+        Some(position) if data[position] == 0x80 => {
             data.truncate(position);
             Ok(())
-        } else {
-            Err("0x80 UNPADDING ERROR: Invalid padding".into())
         }
-    } else {
-        Err("0x80 UNPADDING ERROR: Padding byte not found".into())
+        Some(_) => Err("0x80 UNPADDING ERROR: Invalid padding".into()),
+        None => Err("0x80 UNPADDING ERROR: Padding byte not found".into()),
+    }
+}
+
+/// Check whether the final block of a byte array holds valid 0x80 padding.
+///
+/// The data is valid if its length is a non-zero multiple of `block_size` and
+/// the last non-zero byte of the final block is 0x80, followed only by 0x00
+/// bytes. Only the final block is examined and the data is not modified, so
+/// the check can be used to decide whether to call `unpad_80`.
+///
+/// For block aligned data whose final block is not all zeros, the result
+/// agrees with `unpad_80`. A final block of only 0x00 bytes is rejected, as
+/// `pad_80` never produces it, whereas `unpad_80`, which does not know the
+/// block size, would continue scanning into the previous block.
+///
+/// # Arguments
+///
+/// * `data` : The padded byte array.
+/// * `block_size` : The block size (`usize`) used for padding. Must be greater
+///   than 0 and less than 256.
+///
+/// # Returns
+///
+/// * `true` if the final block holds valid 0x80 padding,
+/// * `false` otherwise, including for empty or unaligned data and an invalid
+///   block size.
+pub fn is_valid_80_padding(data: &[u8], block_size: usize) -> bool {
+    if block_size == 0 || block_size >= 256 || data.is_empty() {
+        return false;
+    }
+
+    if !data.len().is_multiple_of(block_size) {
+        return false;
     }
+
+    let final_block = &data[data.len() - block_size..];
+
+    matches!(
+        final_block.iter().rposition(|&x| x != 0x00),
+        Some(position) if final_block[position] == 0x80
+    )
+}
+
+/// Remove 0x80 padding from the final block in a data-oblivious way.
+///
+/// This is a variant of `unpad_80` for data such as CBC decryption output,
+/// where timing differences could reveal information about the plaintext.
+/// The final block is always scanned completely from the end, accumulating
+/// the padding length with masks instead of early exits or data-dependent
+/// branches. The only data-dependent decision is whether the padding is valid
+/// at all.
+///
+/// The data must be block aligned and the padding must lie within the final
+/// block, as produced by `pad_80`. For such data the result matches
+/// `unpad_80`.
+///
+/// **Note:** Constant-time unpadding does not prevent padding oracle attacks
+/// on its own. Whether unpadding succeeded must not be revealed for
+/// unauthenticated ciphertext; verify a MAC before decrypting.
+///
+/// This function requires the `constant-time` feature.
+///
+/// # Arguments
+///
+/// * `data` : A mutable reference to the padded byte array (`Vec<u8>`).
+/// * `block_size` : The block size (`usize`) used for padding. Must be greater
+///   than 0 and less than 256.
+///
+/// # Returns
+///
+/// * `Ok(())` if the unpadding is successfully performed.
+/// * `Err(Box<dyn Error>)` if the block size is invalid, the data is empty or
+///   not block aligned, or the final block holds no valid padding.
+#[cfg(feature = "constant-time")]
+pub fn unpad_80_ct(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Error>> {
+    if block_size == 0 || block_size >= 256 {
+        return Err(
+            "0x80 UNPADDING ERROR: Block size must be greater than 0 and less than 256".into(),
+        );
+    }
+
+    if data.is_empty() || !data.len().is_multiple_of(block_size) {
+        return Err(format!(
+            "0x80 UNPADDING ERROR: Data must be a non-empty multiple of the block size, got {} bytes",
+            data.len()
+        )
+        .into());
+    }
+
+    let final_block = &data[data.len() - block_size..];
+
+    // All masks are 0x00 or 0xFF
+    let mut found = 0u8;
+    let mut invalid = 0u8;
+    let mut padding_len = 0usize;
+
+    for (i, &byte) in final_block.iter().rev().enumerate() {
+        let nonzero = ((byte | byte.wrapping_neg()) >> 7).wrapping_neg();
+        // Set only for the last non-zero byte, which must be the 0x80 marker
+        let marker = nonzero & !found;
+
+        invalid |= marker & (byte ^ 0x80);
+        padding_len |= (i + 1) & usize::from(marker & 1).wrapping_neg();
+        found |= nonzero;
+    }
+    invalid |= !found;
+
+    if std::hint::black_box(invalid) != 0 {
+        return Err("0x80 UNPADDING ERROR: Invalid padding".into());
+    }
+
+    data.truncate(data.len() - padding_len);
+
+    Ok(())
 }
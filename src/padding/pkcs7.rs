@@ -21,6 +21,9 @@
 //!   This function also modifies the data in place and ensures that the
 //!   unpadding operation is secure and reliable.
 //!
+//! - `has_valid_pkcs7`: Checks whether a byte array ends with well-formed
+//!   PKCS#7 padding without modifying it, e.g. for diagnostics.
+//!
 //! # Usage
 //!
 //! The module is designed to be easily integrated into cryptographic
@@ -77,14 +80,15 @@ use std::error::Error;
 /// # Arguments
 ///
 /// * `data` : A mutable reference to the byte array (`Vec<u8>`) to be padded.
-///            The data is manipulated directly, adding padding in place.
+///   The data is manipulated directly, adding padding in place.
 /// * `block_size` : The block size (`usize`) for padding. Must be greater than
-///                  0 and less than 256.
+///   0 and less than 256.
 ///
 /// # Returns
 ///
 /// * `Ok(())` if the padding is successfully applied,
-/// * `Err(Box<dyn Error>)` if the block size is invalid (0 or >= 256).
+/// * `Err(Box<dyn Error>)` if the block size is invalid (0 or >= 256), or the
+///   padded data would be too large to allocate.
 pub fn pkcs7_pad(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Error>> {
     if block_size == 0 || block_size >= 256 {
         return Err(
@@ -92,7 +96,15 @@ pub fn pkcs7_pad(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Er
         );
     }
 
-    let padding_size = block_size - (data.len() % block_size);
+    let padded_len = super::padded_len(data.len(), block_size).ok_or_else(|| {
+        format!(
+            "PKCS7 PADDING ERROR: Input too large, got {} bytes",
+            data.len()
+        )
+    })?;
+    data.try_reserve_exact(padded_len - data.len())?;
+
+    let padding_size = padded_len - data.len();
     let padding_byte = padding_size as u8;
 
     for _ in 0..padding_size {
@@ -112,14 +124,14 @@ pub fn pkcs7_pad(data: &mut Vec<u8>, block_size: usize) -> Result<(), Box<dyn Er
 /// # Arguments
 ///
 /// * `data` : A mutable reference to the byte array (`Vec<u8>`) from which
-///            padding is to be removed. The data is manipulated directly, with
-///            padding bytes being removed in place.
+///   padding is to be removed. The data is manipulated directly, with
+///   padding bytes being removed in place.
 ///
 /// # Returns
 ///
 /// * `Ok(())` if the unpadding is successfully performed,
 /// * `Err(Box<dyn Error>)` if there's an issue with the padding (e.g.,
-///    inconsistent padding bytes, invalid padding size, or empty input data).
+///   inconsistent padding bytes, invalid padding size, or empty input data).
 pub fn pkcs7_unpad(data: &mut Vec<u8>) -> Result<(), Box<dyn Error>> {
     if data.is_empty() {
         return Err("PKCS7 UNPADDING ERROR: Input data is empty".into());
@@ -150,3 +162,46 @@ pub fn pkcs7_unpad(data: &mut Vec<u8>) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Check whether a byte array ends with well-formed PKCS#7 padding.
+///
+/// Unlike `pkcs7_unpad`, this function does not modify the data and returns a
+/// `bool` instead of an error. It is intended for diagnostics, e.g. to let a
+/// decryption tool report whether the decrypted output looks well-formed. The
+/// check runs in time that depends only on the length of `data`, not on the
+/// padding value or the position of a mismatch.
+///
+/// **Padding oracle warning:** Never reveal the result of this check for
+/// unauthenticated ciphertext to a party that can submit ciphertexts. In CBC
+/// mode, learning whether the padding of a chosen ciphertext is valid is
+/// enough to decrypt arbitrary ciphertexts byte by byte (a padding oracle
+/// attack), regardless of how fast the check itself is. Verify a MAC over the
+/// ciphertext first, as in encrypt-then-MAC.
+///
+/// # Arguments
+///
+/// * `data` : The byte array to check.
+///
+/// # Returns
+///
+/// * `true` if the last byte `n` is in the range 1 to `data.len()` and the
+///   last `n` bytes all equal `n`,
+/// * `false` otherwise, including for empty input.
+pub fn has_valid_pkcs7(data: &[u8]) -> bool {
+    let last = match data.last() {
+        Some(&last) => last,
+        None => return false,
+    };
+    let padding_size = last as usize;
+
+    let mut invalid = (padding_size == 0) as u8 | (padding_size > data.len()) as u8;
+
+    // Inspect the maximum padding length so the work is independent of the
+    // padding value; only bytes inside the padding contribute to the result
+    for (i, &byte) in data.iter().rev().take(255).enumerate() {
+        let in_padding = ((i < padding_size) as u8).wrapping_neg();
+        invalid |= in_padding & (byte ^ last);
+    }
+
+    std::hint::black_box(invalid) == 0
+}
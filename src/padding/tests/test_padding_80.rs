@@ -31,6 +31,32 @@ fn test_pad_80_invalid_block_size_zero() {
     assert!(pad_80(&mut data, block_size).is_err());
 }
 
+#[test]
+fn test_pad_80_invalid_block_size_256() {
+    let mut data = vec![0x01, 0x02, 0x03];
+    let block_size = 256;
+    assert!(pad_80(&mut data, block_size).is_err());
+    assert_eq!(data, vec![0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_pad_80_invalid_block_size_huge() {
+    let mut data = vec![0x01, 0x02, 0x03];
+    let block_size = usize::MAX;
+    assert!(pad_80(&mut data, block_size).is_err());
+    assert_eq!(data, vec![0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_pad_80_max_block_size() {
+    let mut data = vec![0x01, 0x02, 0x03];
+    let block_size = 255;
+    pad_80(&mut data, block_size).unwrap();
+    assert_eq!(data.len(), 255);
+    assert_eq!(data[3], 0x80);
+    assert!(data[4..].iter().all(|&x| x == 0x00));
+}
+
 #[test]
 fn test_unpad_80_valid_padding() {
     let mut data = vec![0x01, 0x02, 0x03, 0x04, 0x80, 0x00, 0x00, 0x00];
@@ -58,3 +84,183 @@ fn test_unpad_80_empty_data() {
     let result = unpad_80(&mut data);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_unpad_80_full_padding_block() {
+    let mut data = vec![0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    unpad_80(&mut data).unwrap();
+    assert!(data.is_empty());
+}
+
+#[test]
+fn test_unpad_80_aligned_payload_full_padding_block() {
+    let mut data = vec![
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+    unpad_80(&mut data).unwrap();
+    assert_eq!(data, vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+}
+
+#[test]
+fn test_pad_80_aligned_adds_full_block() {
+    let payload: Vec<u8> = (1..=16).collect();
+    let mut data = payload.clone();
+    pad_80(&mut data, 16).unwrap();
+
+    assert_eq!(data.len(), 32);
+    assert_eq!(&data[..16], &payload[..]);
+    assert_eq!(data[16], 0x80);
+    assert!(data[17..].iter().all(|&x| x == 0x00));
+}
+
+#[test]
+fn test_pad_unpad_80_aligned_round_trip() {
+    // Payloads ending exactly at a block boundary, including trailing bytes
+    // that look like padding
+    let payloads = [
+        vec![0x11; 16],
+        vec![0x80; 16],
+        vec![0x00; 16],
+        [vec![0x42; 14], vec![0x80, 0x00]].concat(),
+        vec![0xAB; 32],
+    ];
+
+    for payload in payloads {
+        let mut data = payload.clone();
+        pad_80(&mut data, 16).unwrap();
+        assert_eq!(data.len(), payload.len() + 16);
+
+        unpad_80(&mut data).unwrap();
+        assert_eq!(data, payload);
+    }
+}
+
+#[test]
+fn test_pad_unpad_80_round_trip_all_lengths() {
+    for len in 0..=48 {
+        let payload: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37)).collect();
+        let mut data = payload.clone();
+        pad_80(&mut data, 16).unwrap();
+        assert_eq!(data.len() % 16, 0);
+
+        unpad_80(&mut data).unwrap();
+        assert_eq!(data, payload);
+    }
+}
+
+#[test]
+fn test_is_valid_80_padding() {
+    assert!(is_valid_80_padding(&[0x01, 0x02, 0x80, 0x00], 4));
+    assert!(is_valid_80_padding(&[0x01, 0x02, 0x03, 0x80], 4));
+    assert!(is_valid_80_padding(
+        &[0x01, 0x02, 0x03, 0x04, 0x80, 0x00, 0x00, 0x00],
+        4
+    ));
+    assert!(is_valid_80_padding(&[0x80, 0x80, 0x00, 0x00], 4));
+
+    assert!(!is_valid_80_padding(&[0x01, 0x02, 0x03, 0x04], 4));
+    assert!(!is_valid_80_padding(&[0x01, 0x80, 0x01, 0x00], 4));
+    assert!(!is_valid_80_padding(
+        &[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        4
+    ));
+}
+
+#[test]
+fn test_is_valid_80_padding_short_or_invalid_input() {
+    assert!(!is_valid_80_padding(&[], 16));
+    assert!(!is_valid_80_padding(&[0x80], 16));
+    assert!(!is_valid_80_padding(&[0x80, 0x00, 0x00], 2));
+    assert!(!is_valid_80_padding(&[0x80], 0));
+    assert!(!is_valid_80_padding(&[0x80; 256], 256));
+}
+
+#[test]
+fn test_is_valid_80_padding_agrees_with_unpad_80() {
+    // All non-zero 3-byte final blocks over a small alphabet
+    let alphabet = [0x00, 0x01, 0x80];
+    for a in alphabet {
+        for b in alphabet {
+            for c in alphabet {
+                let data = vec![0x42, 0x42, 0x42, a, b, c];
+                if data[3..].iter().all(|&x| x == 0x00) {
+                    continue;
+                }
+
+                let mut unpadded = data.clone();
+                assert_eq!(
+                    is_valid_80_padding(&data, 3),
+                    unpad_80(&mut unpadded).is_ok(),
+                    "disagreement for {:02x?}",
+                    data
+                );
+            }
+        }
+    }
+
+    for len in 0..40 {
+        let mut data = vec![0x80; len];
+        pad_80(&mut data, 16).unwrap();
+        assert!(is_valid_80_padding(&data, 16));
+    }
+}
+
+#[cfg(feature = "constant-time")]
+#[test]
+fn test_unpad_80_ct_matches_unpad_80() {
+    // All 4-byte final blocks over a small alphabet behind a payload block
+    let alphabet = [0x00, 0x01, 0x7f, 0x80, 0xff];
+    for a in alphabet {
+        for b in alphabet {
+            for c in alphabet {
+                for d in alphabet {
+                    let data = vec![0x80, 0x00, 0x42, 0x00, a, b, c, d];
+                    if data[4..].iter().all(|&x| x == 0x00) {
+                        continue;
+                    }
+
+                    let mut expected = data.clone();
+                    let expected_result = unpad_80(&mut expected);
+
+                    let mut actual = data.clone();
+                    let actual_result = unpad_80_ct(&mut actual, 4);
+
+                    assert_eq!(actual_result.is_ok(), expected_result.is_ok());
+                    if actual_result.is_ok() {
+                        assert_eq!(actual, expected);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+#[test]
+fn test_unpad_80_ct_round_trip() {
+    for len in 0..64 {
+        let original: Vec<u8> = (0..len as u8).map(|x| x ^ 0x80).collect();
+        let mut data = original.clone();
+        pad_80(&mut data, 16).unwrap();
+        unpad_80_ct(&mut data, 16).unwrap();
+        assert_eq!(data, original);
+    }
+}
+
+#[cfg(feature = "constant-time")]
+#[test]
+fn test_unpad_80_ct_invalid_input() {
+    assert!(unpad_80_ct(&mut vec![], 16).is_err());
+    assert!(unpad_80_ct(&mut vec![0x80, 0x00, 0x00], 2).is_err());
+    assert!(unpad_80_ct(&mut vec![0x80, 0x00], 0).is_err());
+    assert!(unpad_80_ct(&mut vec![0x80, 0x00, 0x00, 0x00], 2).is_err());
+
+    let mut data = vec![0x01, 0x02, 0x03, 0x04];
+    let result = unpad_80_ct(&mut data, 4);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.to_string(), "0x80 UNPADDING ERROR: Invalid padding");
+    }
+    assert_eq!(data, vec![0x01, 0x02, 0x03, 0x04]);
+}
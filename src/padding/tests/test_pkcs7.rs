@@ -72,3 +72,89 @@ fn test_pkcs7_unpad_invalid_padding_size() {
     let result = pkcs7_unpad(&mut data);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_pkcs7_pad_block_size_256_leaves_data_unchanged() {
+    let mut data = vec![0x01, 0x02, 0x03];
+    assert!(pkcs7_pad(&mut data, 256).is_err());
+    assert_eq!(data, vec![0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_pkcs7_pad_max_block_size() {
+    let mut data = vec![0x01, 0x02, 0x03];
+    pkcs7_pad(&mut data, 255).unwrap();
+
+    assert_eq!(data.len(), 255);
+    assert_eq!(&data[..3], &[0x01, 0x02, 0x03]);
+    assert!(data[3..].iter().all(|&x| x == 252));
+
+    pkcs7_unpad(&mut data).unwrap();
+    assert_eq!(data, vec![0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn test_pkcs7_pad_max_block_size_full_block() {
+    // Aligned input gets a full block of 255 padding bytes of value 0xFF
+    let payload = vec![0xAB; 255];
+    let mut data = payload.clone();
+    pkcs7_pad(&mut data, 255).unwrap();
+
+    assert_eq!(data.len(), 510);
+    assert!(data[255..].iter().all(|&x| x == 0xFF));
+
+    pkcs7_unpad(&mut data).unwrap();
+    assert_eq!(data, payload);
+}
+
+#[test]
+fn test_has_valid_pkcs7_valid() {
+    assert!(has_valid_pkcs7(&[
+        0x01, 0x02, 0x03, 0x05, 0x05, 0x05, 0x05, 0x05
+    ]));
+    assert!(has_valid_pkcs7(&[0xAA, 0x01]));
+    assert!(has_valid_pkcs7(&[0x10; 16]));
+    assert!(has_valid_pkcs7(&[0xFF; 255]));
+
+    let mut data = vec![0x42; 300];
+    pkcs7_pad(&mut data, 16).unwrap();
+    assert!(has_valid_pkcs7(&data));
+}
+
+#[test]
+fn test_has_valid_pkcs7_invalid() {
+    assert!(!has_valid_pkcs7(&[]));
+    assert!(!has_valid_pkcs7(&[0x01, 0x00]));
+    assert!(!has_valid_pkcs7(&[0x03, 0x03]));
+    assert!(!has_valid_pkcs7(&[
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x05, 0x05, 0x05
+    ]));
+    assert!(!has_valid_pkcs7(&[0x05, 0x05, 0x04, 0x05, 0x05]));
+}
+
+#[test]
+fn test_padded_len_boundaries() {
+    use crate::padding::padded_len;
+
+    assert_eq!(padded_len(0, 16), Some(16));
+    assert_eq!(padded_len(15, 16), Some(16));
+    assert_eq!(padded_len(16, 16), Some(32));
+
+    // Pathological lengths are rejected instead of overflowing
+    let max = isize::MAX as usize;
+    assert_eq!(padded_len(usize::MAX, 16), None);
+    assert_eq!(padded_len(max, 16), None);
+    assert_eq!(padded_len(max - 15, 16), None);
+    assert_eq!(padded_len(max - 16, 16), Some(max - 15));
+    assert_eq!(padded_len(usize::MAX, 1), None);
+}
+
+#[test]
+fn test_pkcs7_pad_large_input() {
+    let len = 64 * 1024 * 1024;
+    let mut data = vec![0u8; len];
+    pkcs7_pad(&mut data, 16).expect("Padding failed");
+
+    assert_eq!(data.len(), len + 16);
+    assert_eq!(data[len..], [16u8; 16]);
+}
@@ -0,0 +1,271 @@
+//! CTR_DRBG Deterministic Random Bit Generator
+//!
+//! This module provides a deterministic random bit generator (DRBG) built
+//! solely on AES, following the CTR_DRBG mechanism of NIST SP 800-90A Rev. 1,
+//! section 10.2.1. It uses AES-256 as the block cipher and the variant
+//! without a derivation function. This allows environments that only have AES
+//! available to expand a seed from a true entropy source into a stream of
+//! cryptographically strong pseudorandom bytes.
+//!
+//! # Features
+//!
+//! - `CtrDrbg::new`: Instantiates the DRBG from entropy input, a nonce and an
+//!   optional personalization string.
+//!
+//! - `CtrDrbg::reseed`: Mixes fresh entropy input and optional additional
+//!   input into the internal state.
+//!
+//! - `CtrDrbg::generate` and `CtrDrbg::generate_with_additional_input`: Fill a
+//!   buffer with pseudorandom bytes.
+//!
+//! # Parameters
+//!
+//! For AES-256 without a derivation function, the seed length is 48 bytes:
+//!
+//! - `entropy || nonce` must be exactly 48 bytes long. The variant without a
+//!   derivation function does not use a separate nonce, so the nonce is
+//!   treated as part of the seed material. It may be empty if the entropy
+//!   input already provides all 48 bytes.
+//! - The personalization string and additional inputs are at most 48 bytes.
+//! - At most 65536 bytes can be requested per call to `generate`.
+//! - After 2^48 requests, `generate` fails until the DRBG is reseeded.
+//!
+//! # Example
+//!
+//! ```
+//! use soft_aes::rng::CtrDrbg;
+//!
+//! // In practice, the entropy input must come from a true entropy source
+//! let entropy = [0x42u8; 48];
+//! let mut drbg = CtrDrbg::new(&entropy, &[], b"example").expect("Instantiation failed");
+//!
+//! let mut key = [0u8; 32];
+//! drbg.generate(&mut key).expect("Generation failed");
+//! ```
+//!
+//! # References
+//!
+//! - NIST SP 800-90A Rev. 1: Recommendation for Random Number Generation Using
+//!   Deterministic Random Bit Generators, section 10.2.1
+//!   [https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf]
+//!
+//! # Disclaimer
+//!
+//! - The output is only as unpredictable as the entropy input. This module
+//!   does not gather entropy itself.
+//! - Prediction resistance is not supported. Callers must reseed with fresh
+//!   entropy as required by their security policy.
+
+use crate::aes::{encrypt_block_expanded, internal_key_schedule, AES_256_KEY_SIZE, AES_BLOCK_SIZE};
+
+use std::error::Error;
+
+/// Seed length of CTR_DRBG with AES-256 in bytes (key length + block length).
+pub const CTR_DRBG_SEED_LEN: usize = AES_256_KEY_SIZE + AES_BLOCK_SIZE;
+
+/// Maximum number of bytes returned by a single `generate` request (2^19 bits).
+pub const CTR_DRBG_MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// Maximum number of `generate` requests between reseeds.
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// CTR_DRBG with AES-256 and without a derivation function.
+///
+/// The internal state consists of the AES-256 key, the 128-bit counter `V`
+/// and the reseed counter. The state is not exposed and `CtrDrbg` does not
+/// implement `Debug` or `Clone`, to avoid leaking or duplicating it.
+pub struct CtrDrbg {
+    key: [u8; AES_256_KEY_SIZE],
+    v: [u8; AES_BLOCK_SIZE],
+    reseed_counter: u64,
+}
+
+impl CtrDrbg {
+    /// Instantiate a new DRBG (SP 800-90A, section 10.2.1.3.1).
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy` - The entropy input.
+    /// * `nonce` - The nonce. Together with `entropy` it must be exactly
+    ///   `CTR_DRBG_SEED_LEN` (48) bytes long.
+    /// * `personalization` - Optional personalization string of at most 48
+    ///   bytes. May be empty.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the instantiated DRBG, or an error if an input
+    /// has an invalid length.
+    pub fn new(
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Self, Box<dyn Error>> {
+        if entropy.len() + nonce.len() != CTR_DRBG_SEED_LEN {
+            return Err(format!(
+                "CTR_DRBG ERROR: Entropy input and nonce must be {} bytes long in total, got {} bytes",
+                CTR_DRBG_SEED_LEN,
+                entropy.len() + nonce.len()
+            )
+            .into());
+        }
+
+        let mut seed_material = padded_input(personalization, "Personalization string")?;
+        xor_into(&mut seed_material[..entropy.len()], entropy);
+        xor_into(&mut seed_material[entropy.len()..], nonce);
+
+        let mut drbg = Self {
+            key: [0u8; AES_256_KEY_SIZE],
+            v: [0u8; AES_BLOCK_SIZE],
+            reseed_counter: 1,
+        };
+        drbg.update(&seed_material)?;
+
+        Ok(drbg)
+    }
+
+    /// Reseed the DRBG with fresh entropy (SP 800-90A, section 10.2.1.4.1).
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy` - The entropy input, exactly `CTR_DRBG_SEED_LEN` (48) bytes
+    ///   long.
+    /// * `additional_input` - Optional additional input of at most 48 bytes.
+    ///   May be empty.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the DRBG was reseeded, or an error if an input has an
+    /// invalid length.
+    pub fn reseed(
+        &mut self,
+        entropy: &[u8],
+        additional_input: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        if entropy.len() != CTR_DRBG_SEED_LEN {
+            return Err(format!(
+                "CTR_DRBG ERROR: Entropy input must be {} bytes long, got {} bytes",
+                CTR_DRBG_SEED_LEN,
+                entropy.len()
+            )
+            .into());
+        }
+
+        let mut seed_material = padded_input(additional_input, "Additional input")?;
+        xor_into(&mut seed_material, entropy);
+
+        self.update(&seed_material)?;
+        self.reseed_counter = 1;
+
+        Ok(())
+    }
+
+    /// Fill `out` with pseudorandom bytes (SP 800-90A, section 10.2.1.5.1).
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The buffer to fill, at most `CTR_DRBG_MAX_REQUEST_LEN` bytes.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if `out` was filled, or an error if the request is too large
+    /// or a reseed is required.
+    pub fn generate(&mut self, out: &mut [u8]) -> Result<(), Box<dyn Error>> {
+        self.generate_with_additional_input(out, &[])
+    }
+
+    /// Fill `out` with pseudorandom bytes, mixing in additional input.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The buffer to fill, at most `CTR_DRBG_MAX_REQUEST_LEN` bytes.
+    /// * `additional_input` - Optional additional input of at most 48 bytes.
+    ///   May be empty.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if `out` was filled, or an error if an input is too large or a
+    /// reseed is required. On error, the state of the DRBG is unchanged.
+    pub fn generate_with_additional_input(
+        &mut self,
+        out: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        if out.len() > CTR_DRBG_MAX_REQUEST_LEN {
+            return Err(format!(
+                "CTR_DRBG ERROR: At most {} bytes can be requested at once, got {} bytes",
+                CTR_DRBG_MAX_REQUEST_LEN,
+                out.len()
+            )
+            .into());
+        }
+
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err("CTR_DRBG ERROR: Reseed required".into());
+        }
+
+        let additional_input_block = padded_input(additional_input, "Additional input")?;
+        if !additional_input.is_empty() {
+            self.update(&additional_input_block)?;
+        }
+
+        let (expanded_key, nr) = internal_key_schedule(&self.key)?;
+        for chunk in out.chunks_mut(AES_BLOCK_SIZE) {
+            increment_v(&mut self.v);
+            let block = encrypt_block_expanded(&self.v, &expanded_key, nr);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+
+        self.update(&additional_input_block)?;
+        self.reseed_counter += 1;
+
+        Ok(())
+    }
+
+    /// The CTR_DRBG update function (SP 800-90A, section 10.2.1.2).
+    fn update(&mut self, provided_data: &[u8; CTR_DRBG_SEED_LEN]) -> Result<(), Box<dyn Error>> {
+        let (expanded_key, nr) = internal_key_schedule(&self.key)?;
+
+        let mut temp = [0u8; CTR_DRBG_SEED_LEN];
+        for chunk in temp.chunks_exact_mut(AES_BLOCK_SIZE) {
+            increment_v(&mut self.v);
+            chunk.copy_from_slice(&encrypt_block_expanded(&self.v, &expanded_key, nr));
+        }
+
+        xor_into(&mut temp, provided_data);
+
+        self.key.copy_from_slice(&temp[..AES_256_KEY_SIZE]);
+        self.v.copy_from_slice(&temp[AES_256_KEY_SIZE..]);
+
+        Ok(())
+    }
+}
+
+/// Zero-pad an optional input to the seed length.
+fn padded_input(input: &[u8], name: &str) -> Result<[u8; CTR_DRBG_SEED_LEN], Box<dyn Error>> {
+    if input.len() > CTR_DRBG_SEED_LEN {
+        return Err(format!(
+            "CTR_DRBG ERROR: {} must be at most {} bytes long, got {} bytes",
+            name,
+            CTR_DRBG_SEED_LEN,
+            input.len()
+        )
+        .into());
+    }
+
+    let mut padded = [0u8; CTR_DRBG_SEED_LEN];
+    padded[..input.len()].copy_from_slice(input);
+
+    Ok(padded)
+}
+
+/// Increment `V` as a 128-bit big-endian integer modulo 2^128.
+fn increment_v(v: &mut [u8; AES_BLOCK_SIZE]) {
+    *v = u128::from_be_bytes(*v).wrapping_add(1).to_be_bytes();
+}
+
+/// XOR `data` into `target`, which must be at least as long as `data`.
+fn xor_into(target: &mut [u8], data: &[u8]) {
+    for (t, d) in target.iter_mut().zip(data.iter()) {
+        *t ^= *d;
+    }
+}
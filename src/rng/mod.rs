@@ -0,0 +1,6 @@
+mod ctr_drbg;
+
+pub use ctr_drbg::*;
+
+#[cfg(test)]
+mod tests;
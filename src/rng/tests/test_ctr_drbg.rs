@@ -0,0 +1,130 @@
+use super::super::ctr_drbg::*;
+use hex::decode as hex_decode;
+
+// The expected outputs were computed with the OpenSSL 3 CTR-DRBG
+// (AES-256-CTR, use_derivation_function = 0) and cross-checked against an
+// independent implementation of SP 800-90A, section 10.2.1.
+
+#[test]
+fn test_ctr_drbg_cavp_aes_256_no_df_reseed() {
+    // AES-256 no df vector with no personalization string or additional
+    // input, from the "Basic" test of the BoringSSL and AWS-LC CTR_DRBG
+    // tests (crypto/fipsmodule/rand/ctrdrbg_test.cc). It follows the CAVP
+    // procedure: instantiate, reseed, generate twice, check the second
+    // 512-bit output.
+    let entropy = hex_decode(
+        "e4bc23c5089a19d86f4119cb3fa08c0a4991e0a1def17e101e4c14d9c323460a\
+         7c2fb58e0b086c6c57b55f56cae25bad",
+    )
+    .unwrap();
+    let entropy_reseed = hex_decode(
+        "fd85a836bba85019881e8c6bad23c9061adc75477659acaea8e4a01dfe07a183\
+         2dad1c136f59d70f8653a5dc118663d6",
+    )
+    .unwrap();
+
+    let mut drbg = CtrDrbg::new(&entropy, &[], &[]).unwrap();
+    drbg.reseed(&entropy_reseed, &[]).unwrap();
+
+    let mut out = [0u8; 64];
+    drbg.generate(&mut out).unwrap();
+    drbg.generate(&mut out).unwrap();
+
+    assert_eq!(
+        out.to_vec(),
+        hex_decode(
+            "b2cb8905c05e5950ca31895096be29ea3d5a3b82b269495554eb80fe07de43e1\
+             93b9e7c3ece73b80e062b1c1f68202fbb1c52a040ea2478864295282234aaada"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_ctr_drbg_generate_twice() {
+    // Instantiate, generate 64 bytes, and check the second 64-byte output,
+    // as in the CAVP CTR_DRBG test procedure.
+    let entropy: Vec<u8> = (0x00..0x30).collect();
+    let mut drbg = CtrDrbg::new(&entropy, &[], &[]).unwrap();
+
+    let mut out = [0u8; 64];
+    drbg.generate(&mut out).unwrap();
+    drbg.generate(&mut out).unwrap();
+
+    assert_eq!(
+        out.to_vec(),
+        hex_decode(
+            "04562ad35e8ecafaafda16981cdaa147606beea62801342af13c8b5535f72f94\
+             95b74317c762f0adab7abe710797612176b61b0e208398113cf9c170157bc75f"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_ctr_drbg_nonce_personalization_additional_input() {
+    let entropy: Vec<u8> = (0x80..0xA0).collect();
+    let nonce: Vec<u8> = (0x20..0x30).collect();
+    let mut drbg = CtrDrbg::new(&entropy, &nonce, b"soft-aes personalization").unwrap();
+
+    let mut out = [0u8; 64];
+    drbg.generate_with_additional_input(&mut out, b"additional input 1")
+        .unwrap();
+    assert_eq!(
+        out.to_vec(),
+        hex_decode(
+            "a298dcf501be290082dbe3910c866ef4e9b1aa3e9ed3f6f18bfe15d304b3cb50\
+             f3a702ca7178a233dc9809a6eecb34881409b2b2417f9181cc754704fca16b9b"
+        )
+        .unwrap()
+    );
+
+    drbg.generate_with_additional_input(&mut out, b"additional input 2")
+        .unwrap();
+    assert_eq!(
+        out.to_vec(),
+        hex_decode(
+            "55eb7047ddf915e33d93599e0ef6b9915a05a78a00134bc69f759569988b0903\
+             bf6e7e211ee039dd7976bd48b19a37acfb15ad7fd8618e973901ba7378e14ed8"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_ctr_drbg_reseed_unaligned_output() {
+    let entropy: Vec<u8> = (0x00..0x30).collect();
+    let mut drbg = CtrDrbg::new(&entropy, &[], &[]).unwrap();
+
+    let mut first = [0u8; 16];
+    drbg.generate(&mut first).unwrap();
+    drbg.reseed(&entropy, b"reseed additional input").unwrap();
+
+    let mut out = [0u8; 40];
+    drbg.generate(&mut out).unwrap();
+    assert_eq!(
+        out.to_vec(),
+        hex_decode(
+            "a32f6d5545862c7cec4dc04d2805dcd53e7f9c4455914f4b883632fae98b2540\
+             e0ddd3e133aa9acb"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_ctr_drbg_invalid_lengths() {
+    assert!(CtrDrbg::new(&[0u8; 32], &[], &[]).is_err());
+    assert!(CtrDrbg::new(&[0u8; 32], &[0u8; 17], &[]).is_err());
+    assert!(CtrDrbg::new(&[0u8; 48], &[], &[0u8; 49]).is_err());
+
+    let mut drbg = CtrDrbg::new(&[0u8; 48], &[], &[]).unwrap();
+    assert!(drbg.reseed(&[0u8; 47], &[]).is_err());
+    assert!(drbg.reseed(&[0u8; 48], &[0u8; 49]).is_err());
+
+    let mut out = vec![0u8; CTR_DRBG_MAX_REQUEST_LEN + 1];
+    assert!(drbg.generate(&mut out).is_err());
+    assert!(drbg
+        .generate_with_additional_input(&mut out[..16], &[0u8; 49])
+        .is_err());
+}